@@ -1,7 +1,9 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput};
 
 #[proc_macro_derive(Builder, attributes(builder))]
@@ -10,141 +12,1249 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
     // dbg!(input.clone());
 
-    // fetch name, make name + Builder version
+    match expand(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+// what a single builder builds: the input struct, or one variant of the input enum
+struct Target<'a> {
+    input: &'a DeriveInput,
+    attrs: &'a StructAttrs,
+    fields: &'a syn::Fields,
+    builder_ident: syn::Ident,
+    // path used to construct the target, e.g. `Shape::Circle`
+    ctor: proc_macro2::TokenStream,
+    // associated function on the input type that returns an empty builder
+    builder_fn: syn::Ident,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_attrs = get_struct_attrs(&input.attrs)?;
     let input_ident = &input.ident;
-    let builder_name = format!("{}Builder", input_ident);
-    let builder_ident = syn::Ident::new(&builder_name, input_ident.span());
 
-    // get list of fields in the input struct
-    let fields = if let syn::Data::Struct(syn::DataStruct {
-        fields: syn::Fields::Named(syn::FieldsNamed { ref named, .. }),
-        ..
-    }) = input.data
-    {
-        named
+    match input.data {
+        syn::Data::Struct(ref data) => expand_builder(&Target {
+            input,
+            attrs: &struct_attrs,
+            fields: &data.fields,
+            // fetch name, make name + Builder version
+            builder_ident: match struct_attrs.name {
+                Some(ref name) => name.clone(),
+                None => format_ident!("{}Builder", input_ident),
+            },
+            ctor: quote!(#input_ident),
+            builder_fn: format_ident!("builder"),
+        }),
+        syn::Data::Enum(ref data) => {
+            if struct_attrs.default {
+                return Err(syn::Error::new_spanned(
+                    data.enum_token,
+                    "`builder(default)` is not supported on enums",
+                ));
+            }
+            if let Some(ref name) = struct_attrs.name {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`builder(name = \"...\")` is not supported on enums, every variant gets a builder of its own",
+                ));
+            }
+
+            // every variant gets a builder of its own, e.g. Shape::circle_builder() returning a
+            // ShapeCircleBuilder
+            data.variants
+                .iter()
+                .map(|variant| {
+                    // variants only share the enum's options, one given on a variant would be
+                    // ignored
+                    for attr in variant.attrs.iter().filter(|a| a.path.is_ident("builder")) {
+                        if let Some(nested) = builder_options(attr)?.first() {
+                            return Err(syn::Error::new_spanned(
+                                nested,
+                                "options are not supported on enum variants, put them on the enum or on the variant's fields",
+                            ));
+                        }
+                    }
+
+                    let variant_ident = &variant.ident;
+                    let snake_name = to_snake_case(&variant_ident.unraw().to_string());
+                    expand_builder(&Target {
+                        input,
+                        attrs: &struct_attrs,
+                        fields: &variant.fields,
+                        builder_ident: format_ident!("{}{}Builder", input_ident, variant_ident),
+                        ctor: quote!(#input_ident::#variant_ident),
+                        builder_fn: format_ident!("{}_builder", snake_name),
+                    })
+                })
+                .collect()
+        }
+        syn::Data::Union(ref data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "Builder cannot be derived for unions",
+        )),
+    }
+}
+
+fn expand_builder(target: &Target) -> syn::Result<proc_macro2::TokenStream> {
+    let input_ident = &target.input.ident;
+    let struct_attrs = target.attrs;
+
+    // no_std crates only have what's in core and alloc
+    let (core, alloc) = if struct_attrs.no_std {
+        (quote!(::core), quote!(::alloc))
+    } else {
+        (quote!(::std), quote!(::std))
+    };
+    let builder_ident = &target.builder_ident;
+    let error_ident = format_ident!("{}Error", builder_ident);
+    let ctor = &target.ctor;
+    let builder_fn = &target.builder_fn;
+
+    // everything generated is as visible as the input unless asked otherwise
+    let vis = struct_attrs.vis.as_ref().unwrap_or(&target.input.vis);
+
+    // build() returns our own error enum unless the caller asked for their own type, which
+    // then has to implement From<#error_ident>
+    let build_error = match struct_attrs.error {
+        Some(ref path) => quote!(#path),
+        None => quote!(#error_ident),
+    };
+
+    // carry the input's generics (including defaults and where-clauses) over to the builder
+    let generics = &target.input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // parse every field's #[builder(...)] attributes up front so errors surface before codegen
+    let fields = target
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = get_field_attrs(field, &core)?;
+
+            // tuple fields are set through _0, _1, ... unless they are given a name
+            let (ident, member) = match field.ident {
+                Some(ref ident) => (ident.clone(), syn::Member::Named(ident.clone())),
+                None => (
+                    format_ident!("_{}", i),
+                    syn::Member::Unnamed(syn::Index::from(i)),
+                ),
+            };
+            let ident = attrs.name.clone().unwrap_or(ident);
+
+            // setter names are prefixed unless given in full
+            let prefix = attrs
+                .prefix
+                .as_ref()
+                .or(struct_attrs.prefix.as_ref())
+                .map_or("", String::as_str);
+            let setter = match attrs.setter_name {
+                Some(ref name) => name.clone(),
+                None if prefix.is_empty() => ident.clone(),
+                None => format_ident!("{}{}", prefix, ident.unraw()),
+            };
+            let each = attrs.each.as_ref().map(|each| {
+                if prefix.is_empty() {
+                    each.clone()
+                } else {
+                    format_ident!("{}{}", prefix, each.unraw(), span = each.span())
+                }
+            });
+            let extend = attrs.each.as_ref().map(|each| {
+                format_ident!("{}extend_{}", prefix, ident.unraw(), span = each.span())
+            });
+
+            // a field without a setter has to get its value from somewhere else
+            if let Some(ref skip) = attrs.skip {
+                if is_required(field, &attrs, struct_attrs) {
+                    return Err(syn::Error::new_spanned(
+                        skip,
+                        "`setter(skip)` requires a default for the field",
+                    ));
+                }
+            }
+
+            // only an async build() can await an initialiser
+            if let Some(ref init) = attrs.default_with {
+                if attrs.default_with_async && !struct_attrs.is_async {
+                    return Err(syn::Error::new_spanned(
+                        init,
+                        "`default_with_async` requires `build_fn(async)` on the struct",
+                    ));
+                }
+            }
+
+            let sub_builder = match attrs.sub_builder {
+                Some(ref attr) => Some(sub_builder_ty(&field.ty, attr.span())?),
+                None => None,
+            };
+
+            // fallible conversions only apply to setters taking the whole value
+            let try_setter = if (attrs.try_into || struct_attrs.try_into)
+                && attrs.skip.is_none()
+                && each.is_none()
+                && sub_builder.is_none()
+            {
+                Some(format_ident!(
+                    "try_{}",
+                    setter.unraw(),
+                    span = setter.span()
+                ))
+            } else {
+                None
+            };
+
+            // in typestate mode, every field that must be set before build() gets a type
+            // parameter on the builder which starts out as () and becomes the field's type once
+            // its setter is called; named after the field's position, since names derived from
+            // the field's own could collide
+            let state = if struct_attrs.typestate && is_required(field, &attrs, struct_attrs) {
+                Some(format_ident!("__State{}", i))
+            } else {
+                None
+            };
+
+            Ok(BuilderField {
+                field,
+                ident,
+                setter,
+                each,
+                extend,
+                try_setter,
+                member,
+                attrs,
+                state,
+                sub_builder,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let states = fields
+        .iter()
+        .filter_map(|f| f.state.as_ref())
+        .collect::<Vec<_>>();
+
+    // every setter has to end up with a name of its own
+    let mut setter_names = std::collections::HashMap::new();
+    for f in fields.iter().filter(|f| f.attrs.skip.is_none()) {
+        let mut names = Vec::new();
+        if let (Some(each), Some(extend)) = (&f.each, &f.extend) {
+            names.push(each.clone());
+            names.push(extend.clone());
+            // a collection whose each setter takes over the field's own name only loses the
+            // setter taking the whole collection, unless that one was named explicitly
+            if f.attrs.setter_name.is_some() || *each != f.setter {
+                names.push(f.setter.clone());
+            }
+        } else {
+            names.push(f.setter.clone());
+        }
+        names.extend(f.try_setter.clone());
+        for name in names {
+            let field_name = f.ident.unraw().to_string();
+            if let Some(other) = setter_names.insert(name.unraw().to_string(), field_name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "setter `{}` is already generated for field `{}`",
+                        name.unraw(),
+                        other
+                    ),
+                ));
+            }
+        }
+    }
+
+    // the builder struct declares every state as unset by default, impl blocks that work in any
+    // state are generic over all of them
+    let mut builder_generics = generics.clone();
+    let mut state_generics = generics.clone();
+    for state in &states {
+        builder_generics.params.push(syn::parse_quote!(#state = ()));
+        state_generics.params.push(syn::parse_quote!(#state));
+    }
+    let (state_impl_generics, state_ty_generics, _) = state_generics.split_for_impl();
+
+    let unset_builder_ty = builder_ty(
+        builder_ident,
+        generic_args(generics).chain(states.iter().map(|_| quote!(()))),
+    );
+    let set_builder_ty = builder_ty(
+        builder_ident,
+        generic_args(generics).chain(fields.iter().filter(|f| f.state.is_some()).map(|f| {
+            let ty = &f.field.ty;
+            quote!(#ty)
+        })),
+    );
+
+    let deserialize = struct_attrs
+        .derives
+        .iter()
+        .any(|d| d.is_ident("Deserialize"));
+    let serialize = struct_attrs.derives.iter().any(|d| d.is_ident("Serialize"));
+
+    // a typestate builder doesn't necessarily mention all of the input's generic parameters in
+    // its fields, so it holds on to a marker that does
+    let phantom = if generics.params.is_empty() {
+        None
     } else {
-        panic!("Non-struct found!");
+        Some(format_ident!("__phantom"))
     };
+    let phantom_decl = phantom.as_ref().map(|p| {
+        let skip = if !(deserialize || serialize) {
+            quote!()
+        } else {
+            quote!(#[serde(skip)])
+        };
+        quote! {
+            #skip
+            #p: #core::marker::PhantomData<fn() -> #input_ident #ty_generics>,
+        }
+    });
+    let phantom_init = phantom.as_ref().map(|p| {
+        quote! {
+            #p: #core::marker::PhantomData,
+        }
+    });
+
+    let pattern = struct_attrs.pattern;
+    let this = setter_receiver(pattern);
 
     let builder_fields = fields.iter().map(|f| {
         let name = &f.ident;
-        let ty = &f.ty;
-        if get_inner_ty(ty, "Option").is_some() || has_each_attr(f) {
+        let ty = &f.field.ty;
+        let serde = &f.attrs.serde;
+        let passed_on = &f.attrs.attrs;
+        // serialized builders only mention the fields that were actually set, except for `each`
+        // collections which are always there, and fields without a setter can't be set through
+        // serde either
+        let skip_unset = if (serialize || deserialize) && f.attrs.skip.is_some() {
+            quote!(#[serde(skip)])
+        } else if serialize && f.attrs.each.is_none() {
+            let is_none = format!("{}::option::Option::is_none", core);
+            quote!(#[serde(skip_serializing_if = #is_none)])
+        } else {
+            quote!()
+        };
+        let attrs = quote! {
+            #(#[serde(#serde)])*
+            #skip_unset
+            #(#[#passed_on])*
+        };
+        if let Some(ref state) = f.state {
             quote! {
+                #attrs
+                #name: #state
+            }
+        } else if let Some(ref sub_builder) = f.sub_builder {
+            quote! {
+                #attrs
+                #name: #core::option::Option<#sub_builder>
+            }
+        } else if get_inner_ty(ty, "Option").is_some() || f.attrs.each.is_some() {
+            quote! {
+                #attrs
                 #name: #ty
             }
         } else {
             quote! {
-                #name: std::option::Option<#ty>
+                #attrs
+                #name: #core::option::Option<#ty>
             }
         }
     });
 
     let builder_empty_fields = fields.iter().map(|f| {
         let name = &f.ident;
-        if has_each_attr(f) {
+        if f.state.is_some() {
+            quote! {
+                #name: ()
+            }
+        } else if f.attrs.each.is_some() {
             quote! {
-                #name: std::vec::Vec::new()
+                #name: #core::default::Default::default()
             }
         } else {
             quote! {
-                #name: std::option::Option::None
+                #name: #core::option::Option::None
             }
         }
     });
 
-    let builder_methods = fields.iter().map(|f| {
-        let name = &f.ident.clone().unwrap();
-        let ty = match get_inner_ty(&f.ty, "Option") {
-            Some(s) => s,
-            None => f.ty.clone(),
+    let builder_methods = fields.iter().filter(|f| f.attrs.skip.is_none()).map(|f| {
+        let name = &f.ident;
+        let setter_name = &f.setter;
+
+        // setters carry the field's own docs, followed by a note on how the setter behaves
+        let field_docs = f
+            .field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .collect::<Vec<_>>();
+        let docs = |note: &str| {
+            let separator = if field_docs.is_empty() {
+                quote!()
+            } else {
+                quote!(#[doc = ""])
+            };
+            quote! {
+                #(#field_docs)*
+                #separator
+                #[doc = #note]
+            }
+        };
+        let requirement = if f.state.is_some() {
+            "This field is required, `build()` only becomes available once it is set."
+        } else if is_required(f.field, &f.attrs, struct_attrs) {
+            "This field is required, `build()` fails if it is never set."
+        } else if f.attrs.default.is_some()
+            || f.attrs.default_with.is_some()
+            || struct_attrs.default
+        {
+            "This field is optional, `build()` falls back to its default if it is never set."
+        } else {
+            "This field is optional."
         };
+        let value_docs = docs(requirement);
 
-        let each_attr = match get_each_attr(&f) {
-            Ok(s) => s,
-            Err(e) => return e,
+        // Option<T> fields are set with a plain T unless asked not to
+        let inner_ty = get_inner_ty(&f.field.ty, "Option");
+        let keeps_option = inner_ty.is_some() && f.attrs.keep_option;
+        let ty = match inner_ty {
+            Some(inner_ty) if !keeps_option => inner_ty,
+            _ => f.field.ty.clone(),
         };
 
-        match each_attr {
-            Some(s) => {
-                let s = s.trim_end_matches("\"");
-                // wrong span, but w/e
-                let s = syn::Ident::new(s, name.span());
-                let vec_ty = get_inner_ty(&f.ty, "Vec");
-                if s.eq(&name.to_string()) {
+        // with setter(into), setters accept anything that converts into the field's type
+        let (setter_generics, arg_ty, value) = if f.attrs.into || struct_attrs.into {
+            (
+                quote!(<__V: #core::convert::Into<#ty>>),
+                quote!(__V),
+                quote!(#core::convert::Into::into(#name)),
+            )
+        } else {
+            (quote!(), quote!(#ty), quote!(#name))
+        };
+
+        // try setters convert their argument and hand it to the plain setter, returning whatever
+        // that one returns
+        let try_setter = |ret: proc_macro2::TokenStream| {
+            let try_name = match f.try_setter {
+                Some(ref try_name) => try_name,
+                None => return quote!(),
+            };
+            let receiver = match pattern {
+                Pattern::Owned => quote!(self),
+                Pattern::Mutable => quote!(&mut self),
+                Pattern::Immutable => quote!(&self),
+            };
+            let docs = docs(&format!(
+                "Like `{}`, but converts the value with `TryInto` first and returns the error if \
+                 that fails.",
+                setter_name.unraw()
+            ));
+            quote! {
+                #docs
+                #vis fn #try_name<__V: #core::convert::TryInto<#ty>>(
+                    #receiver,
+                    #name: __V,
+                ) -> #core::result::Result<#ret, <__V as #core::convert::TryInto<#ty>>::Error> {
+                    let #name: #ty = #core::convert::TryInto::try_into(#name)?;
+                    #core::result::Result::Ok(self.#setter_name(#name))
+                }
+            }
+        };
+        let pattern_ret = match pattern {
+            Pattern::Mutable => quote!(&mut Self),
+            Pattern::Owned | Pattern::Immutable => quote!(Self),
+        };
+
+        if let Some(ref sub_builder) = f.sub_builder {
+            // nested builders are configured in place, whatever the outer builder's pattern
+            let ty = &f.field.ty;
+            let span = f.attrs.sub_builder.span();
+            let docs = docs(
+                "Configures this field through its own builder, which `build()` builds along with \
+                 this one.",
+            );
+            let get_or_insert =
+                quote_spanned!(span=> self.#name.get_or_insert_with(<#ty>::builder));
+            return quote! {
+                #docs
+                #vis fn #setter_name(&mut self) -> &mut #sub_builder {
+                    #get_or_insert
+                }
+            };
+        }
+
+        if let Some(ref state) = f.state {
+            // move every field over into a builder whose state for this field is "set"
+            let next_ty = builder_ty(
+                builder_ident,
+                generic_args(generics).chain(states.iter().map(|s| {
+                    if *s == state {
+                        quote!(#ty)
+                    } else {
+                        quote!(#s)
+                    }
+                })),
+            );
+            let others = fields
+                .iter()
+                .map(|o| &o.ident)
+                .filter(|o| *o != name)
+                .chain(&phantom);
+            let try_setter = try_setter(next_ty.clone());
+            return quote! {
+                #try_setter
+                #value_docs
+                #vis fn #setter_name #setter_generics(self, #name: #arg_ty) -> #next_ty {
+                    #builder_ident {
+                        #name: #value,
+                        #(#others: self.#others,)*
+                    }
+                }
+            };
+        }
+
+        match (&f.each, &f.extend) {
+            (Some(s), Some(extend)) => {
+                // anything that is Default + Extend works; its IntoIterator::Item is what the
+                // one-at-a-time setter takes, which is a (key, value) pair for maps
+                let coll_ty = &f.field.ty;
+                let item_ty = quote!(<#coll_ty as #core::iter::IntoIterator>::Item);
+                let each_setter = setter(
+                    &core,
+                    pattern,
+                    vis,
+                    s,
+                    quote!(),
+                    quote!(#s: #item_ty),
                     quote! {
-                        fn #s(&mut self, #s: #vec_ty) -> &mut Self {
-                            self.#name.push(#s);
-                            self
-                        }
+                        #core::iter::Extend::extend(&mut #this.#name, #core::iter::once(#s));
+                    },
+                );
+                let extend_setter = setter(
+                    &core,
+                    pattern,
+                    vis,
+                    extend,
+                    quote!(<__I: #core::iter::IntoIterator<Item = #item_ty>>),
+                    quote!(#name: __I),
+                    quote! {
+                        #core::iter::Extend::extend(&mut #this.#name, #name);
+                    },
+                );
+                let each_docs =
+                    docs("Adds a single element to this collection, which starts out empty.");
+                let extend_docs = docs("Adds every element of an iterator to this collection.");
+                if s == setter_name {
+                    quote! {
+                        #each_docs
+                        #each_setter
+                        #extend_docs
+                        #extend_setter
                     }
                 } else {
+                    let all_setter = setter(
+                        &core,
+                        pattern,
+                        vis,
+                        setter_name,
+                        setter_generics,
+                        quote!(#name: #arg_ty),
+                        quote! {
+                            #this.#name = #value;
+                        },
+                    );
+                    let all_docs = docs("Replaces everything added to this collection so far.");
                     quote! {
-                        fn #s(&mut self, #s: #vec_ty) -> &mut Self {
-                            self.#name.push(#s);
-                            self
-                        }
-                        fn #name(&mut self, #name: #ty) -> &mut Self {
-                            self.#name = #name;
-                            self
-                        }
+                        #each_docs
+                        #each_setter
+                        #extend_docs
+                        #extend_setter
+                        #all_docs
+                        #all_setter
                     }
                 }
             }
+            // the builder stores an Option<T> field as is, so a setter taking the whole Option
+            // can assign it directly
+            _ if keeps_option => {
+                let value_setter = setter(
+                    &core,
+                    pattern,
+                    vis,
+                    setter_name,
+                    setter_generics,
+                    quote!(#name: #arg_ty),
+                    quote! {
+                        #this.#name = #value;
+                    },
+                );
+                let try_setter = try_setter(pattern_ret);
+                quote! {
+                    #value_docs
+                    #value_setter
+                    #try_setter
+                }
+            }
             _ => {
+                let value_setter = setter(
+                    &core,
+                    pattern,
+                    vis,
+                    setter_name,
+                    setter_generics,
+                    quote!(#name: #arg_ty),
+                    quote! {
+                        #this.#name = #core::option::Option::Some(#value);
+                    },
+                );
+                let try_setter = try_setter(pattern_ret);
                 quote! {
-                    fn #name(&mut self, #name: #ty) -> &mut Self {
-                        self.#name = std::option::Option::Some(#name);
-                        self
-                    }
+                    #value_docs
+                    #value_setter
+                    #try_setter
                 }
             }
         }
     });
 
+    // with a struct-level #[builder(default)], unset fields are taken from the struct's Default
+    let struct_default = if struct_attrs.default {
+        quote! {
+            let __default: #input_ident #ty_generics = #core::default::Default::default();
+        }
+    } else {
+        quote!()
+    };
+
     let input_fields = fields.iter().map(|f| {
         let name = &f.ident;
+        let name_str = name.unraw().to_string();
+        let member = &f.member;
 
-        if get_inner_ty(&f.ty, "Option").is_none() && !has_each_attr(f) {
-            quote! {
-                #name: self.#name.clone().ok_or_else(|| "Not implemented")?
+        // the field's own default, only ever evaluated for unset fields
+        let await_init = if f.attrs.default_with_async {
+            quote!(.await)
+        } else {
+            quote!()
+        };
+        let default = match (&f.attrs.default, &f.attrs.default_with) {
+            (Some(expr), _) => Some(quote!(#expr)),
+            (None, Some(init)) => Some(quote! {
+                match #init()#await_init {
+                    #core::result::Result::Ok(v) => v,
+                    #core::result::Result::Err(e) => {
+                        return #core::result::Result::Err(#core::convert::From::from(
+                            #error_ident::DefaultError {
+                                field: #name_str,
+                                message: #alloc::string::ToString::to_string(&e),
+                            },
+                        ))
+                    }
+                }
+            }),
+            (None, None) => None,
+        };
+
+        if f.state.is_some() {
+            return quote! {
+                #member: self.#name
+            };
+        }
+
+        if f.attrs.each.is_some() {
+            let items = if pattern == Pattern::Owned {
+                quote!(self.#name)
+            } else {
+                quote!(#core::clone::Clone::clone(&self.#name))
+            };
+            if !struct_attrs.default {
+                return quote! {
+                    #member: #items
+                };
             }
+
+            // a collection counts as set once it holds anything, an empty one is taken from the
+            // struct's Default like any other unset field
+            let ty = &f.field.ty;
+            return quote! {
+                #member: {
+                    let mut items = #core::iter::Iterator::peekable(
+                        #core::iter::IntoIterator::into_iter(#items),
+                    );
+                    if #core::option::Option::is_some(&items.peek()) {
+                        let mut collection: #ty = #core::default::Default::default();
+                        #core::iter::Extend::extend(&mut collection, items);
+                        collection
+                    } else {
+                        __default.#member
+                    }
+                }
+            };
+        }
+
+        if f.sub_builder.is_some() {
+            // a nested builder that was never touched is built empty, which succeeds if all of
+            // its fields are optional
+            let ty = &f.field.ty;
+            let span = f.attrs.sub_builder.span();
+            let fallback = match default {
+                Some(default) => quote!(#core::result::Result::Ok(#default)),
+                None if struct_attrs.default => {
+                    quote!(#core::result::Result::Ok(__default.#member))
+                }
+                None => quote_spanned!(span=> <#ty>::builder().build()),
+            };
+            let (binding, sub_builder) = match pattern {
+                Pattern::Owned => (quote!(b), quote!({ b })),
+                Pattern::Mutable => (quote!(ref mut b), quote!(b)),
+                Pattern::Immutable => (quote!(ref b), quote!(#core::clone::Clone::clone(b))),
+            };
+            let build = quote_spanned!(span=> #sub_builder.build());
+            return quote! {
+                #member: match match self.#name {
+                    #core::option::Option::Some(#binding) => #build,
+                    #core::option::Option::None => #fallback,
+                } {
+                    #core::result::Result::Ok(v) => v,
+                    #core::result::Result::Err(e) => {
+                        return #core::result::Result::Err(#core::convert::From::from(
+                            #error_ident::SubBuilderError {
+                                field: #name_str,
+                                message: #alloc::string::ToString::to_string(&e),
+                            },
+                        ))
+                    }
+                }
+            };
+        }
+
+        let is_option = get_inner_ty(&f.field.ty, "Option").is_some();
+        let fallback = match default {
+            Some(default) => default,
+            None if struct_attrs.default => quote!(__default.#member),
+            None if is_option => quote!(#core::option::Option::None),
+            None => quote! {
+                return #core::result::Result::Err(
+                    #core::convert::From::from(#error_ident::MissingField(#name_str))
+                )
+            },
+        };
+
+        let (binding, value) = if pattern == Pattern::Owned {
+            (quote!(v), quote!(v))
         } else {
-            quote! {
-                #name: self.#name.clone()
+            (quote!(ref v), quote!(#core::clone::Clone::clone(v)))
+        };
+        let value = if is_option {
+            quote!(#core::option::Option::Some(#value))
+        } else {
+            value
+        };
+
+        quote! {
+            #member: match self.#name {
+                #core::option::Option::Some(#binding) => #value,
+                #core::option::Option::None => #fallback,
             }
         }
     });
 
+    // validators only see values the caller actually set, defaults are trusted
+    let field_validations = fields.iter().filter_map(|f| {
+        let validate = f.attrs.validate.as_ref()?;
+        let name = &f.ident;
+        let name_str = name.unraw().to_string();
+        let value = if f.state.is_some() || f.attrs.each.is_some() {
+            quote!(#core::option::Option::Some(&self.#name))
+        } else {
+            quote!(#core::option::Option::as_ref(&self.#name))
+        };
+        Some(quote! {
+            if let #core::option::Option::Some(v) = #value {
+                if let #core::result::Result::Err(e) = #validate(v) {
+                    return #core::result::Result::Err(#core::convert::From::from(
+                        #error_ident::ValidationError {
+                            field: #core::option::Option::Some(#name_str),
+                            message: #core::convert::Into::into(e),
+                        },
+                    ));
+                }
+            }
+        })
+    });
+
+    let struct_validation = struct_attrs.validate.as_ref().map(|validate| {
+        quote! {
+            if let #core::result::Result::Err(e) = #validate(&self) {
+                return #core::result::Result::Err(#core::convert::From::from(
+                    #error_ident::ValidationError {
+                        field: #core::option::Option::None,
+                        message: #core::convert::Into::into(e),
+                    },
+                ));
+            }
+        }
+    });
+
+    let build_async = if struct_attrs.is_async {
+        quote!(async)
+    } else {
+        quote!()
+    };
+    let build_receiver = match pattern {
+        Pattern::Owned => quote!(self),
+        Pattern::Mutable => quote!(&mut self),
+        Pattern::Immutable => quote!(&self),
+    };
+
+    // immutable setters hand out updated copies of the builder
+    let mut derives = Vec::new();
+    let derives_clone = struct_attrs.derives.iter().any(|d| d.is_ident("Clone"));
+    if pattern == Pattern::Immutable && !derives_clone {
+        derives.push(quote!(#core::clone::Clone));
+    }
+    derives.extend(struct_attrs.derives.iter().map(|d| {
+        if is_serde_trait(d) {
+            quote!(::serde::#d)
+        } else {
+            quote!(#d)
+        }
+    }));
+    let builder_derives = if derives.is_empty() {
+        quote!()
+    } else {
+        quote!(#[derive(#(#derives),*)])
+    };
+    let serde = &struct_attrs.serde;
+    let attrs = &struct_attrs.attrs;
+    let builder_attrs = quote! {
+        #builder_derives
+        #(#[serde(#serde)])*
+        #(#[#attrs])*
+    };
+
+    // layering builders only makes sense when both sides have the same type, which typestate
+    // builders in different states don't
+    let merge_method = if struct_attrs.typestate {
+        None
+    } else {
+        let merges = fields.iter().map(|f| {
+            let name = &f.ident;
+            if f.attrs.each.is_some() && f.attrs.merge_replace {
+                // a collection counts as set once it holds anything
+                quote! {
+                    let mut items = #core::iter::Iterator::peekable(
+                        #core::iter::IntoIterator::into_iter(other.#name),
+                    );
+                    if #core::option::Option::is_some(&items.peek()) {
+                        self.#name = #core::default::Default::default();
+                        #core::iter::Extend::extend(&mut self.#name, items);
+                    }
+                }
+            } else if f.attrs.each.is_some() {
+                quote! {
+                    #core::iter::Extend::extend(&mut self.#name, other.#name);
+                }
+            } else if f.sub_builder.is_some() {
+                // nested builders are layered field by field as well
+                let merge = quote_spanned!(f.attrs.sub_builder.span()=> ours.merge(theirs));
+                quote! {
+                    match (&mut self.#name, other.#name) {
+                        (#core::option::Option::Some(ours), #core::option::Option::Some(theirs)) => {
+                            #merge;
+                        }
+                        (ours, theirs @ #core::option::Option::Some(_)) => *ours = theirs,
+                        _ => {}
+                    }
+                }
+            } else {
+                quote! {
+                    if #core::option::Option::is_some(&other.#name) {
+                        self.#name = other.#name;
+                    }
+                }
+            }
+        });
+        Some(quote! {
+            /// Overrides every field with the one from `other` if that was set. Collections with
+            /// an `each` setter are appended to unless declared `merge = "replace"`.
+            #vis fn merge(&mut self, other: Self) -> &mut Self {
+                #(#merges)*
+                self
+            }
+        })
+    };
+
+    // seeds a builder with every field of an existing value; only structs have a single shape
+    // to take apart, and the owned pattern avoids requiring Clone by taking the value itself
+    let from_target_method = match target.input.data {
+        syn::Data::Struct(_) => {
+            let owned = pattern == Pattern::Owned;
+            let seeded_fields = fields.iter().map(|f| {
+                let name = &f.ident;
+                let member = &f.member;
+                let seed = if owned {
+                    quote!(target.#member)
+                } else {
+                    quote!(#core::clone::Clone::clone(&target.#member))
+                };
+                if let Some(ref sub_builder) = f.sub_builder {
+                    // the nested builder seeds itself, so its type doesn't have to be Clone
+                    let span = f.attrs.sub_builder.span();
+                    let mut member = member.clone();
+                    if let syn::Member::Named(ref mut ident) = member {
+                        ident.set_span(span);
+                    }
+                    let seed = if owned {
+                        quote_spanned!(span=> target.#member)
+                    } else {
+                        quote_spanned!(span=> &target.#member)
+                    };
+                    let from_target = quote_spanned!(span=> <#sub_builder>::from_target(#seed));
+                    quote! {
+                        #name: #core::option::Option::Some(#from_target)
+                    }
+                } else if f.state.is_some()
+                    || f.attrs.each.is_some()
+                    || get_inner_ty(&f.field.ty, "Option").is_some()
+                {
+                    quote! {
+                        #name: #seed
+                    }
+                } else {
+                    quote! {
+                        #name: #core::option::Option::Some(#seed)
+                    }
+                }
+            });
+            let (target_generics, target_ty) = if owned {
+                (quote!(), quote!(#input_ident #ty_generics))
+            } else {
+                (
+                    quote!(<__T: #core::borrow::Borrow<#input_ident #ty_generics>>),
+                    quote!(__T),
+                )
+            };
+            let target_binding = if owned {
+                quote!()
+            } else {
+                quote! {
+                    let target = #core::borrow::Borrow::borrow(&target);
+                }
+            };
+            Some(quote! {
+                /// Starts a builder with every field set to the one of `target`.
+                #vis fn from_target #target_generics(target: #target_ty) -> Self {
+                    #target_binding
+                    #builder_ident {
+                        #(#seeded_fields,)*
+                        #phantom_init
+                    }
+                }
+            })
+        }
+        _ => None,
+    };
+
+    // a deserialized builder starts out empty, so partial documents only set what they mention,
+    // and can then be layered on top of a builder configured in code
+    let (deserialize_methods, default_impl) = if deserialize {
+        let methods = quote! {
+            /// Sets every field mentioned by the deserializer's document, appending to
+            /// collections, and leaves the others as they are.
+            #vis fn merge_deserialized<'de, __D>(
+                &mut self,
+                deserializer: __D,
+            ) -> #core::result::Result<&mut Self, __D::Error>
+            where
+                __D: ::serde::Deserializer<'de>,
+                Self: ::serde::Deserialize<'de>,
+            {
+                let other: Self = ::serde::Deserialize::deserialize(deserializer)?;
+                #core::result::Result::Ok(self.merge(other))
+            }
+        };
+        let default_impl = quote! {
+            impl #impl_generics #core::default::Default for #unset_builder_ty #where_clause {
+                fn default() -> Self {
+                    <#input_ident #ty_generics>::#builder_fn()
+                }
+            }
+        };
+        (methods, default_impl)
+    } else {
+        (quote!(), quote!())
+    };
+    let serde_default = if deserialize {
+        quote!(#[serde(default)])
+    } else {
+        quote!()
+    };
+
+    // rustdoc for the builder, naming what has to be set before build()
+    let target_name = ctor.to_string().replace(' ', "");
+    let required = fields
+        .iter()
+        .filter(|f| f.state.is_some() || is_required(f.field, &f.attrs, struct_attrs))
+        .map(|f| format!("`{}`", f.setter.unraw()))
+        .collect::<Vec<_>>();
+    let builder_doc = format!("Builder for [`{}`].", target_name);
+    let required_doc = if required.is_empty() {
+        "No field has to be set before calling `build()`.".to_owned()
+    } else {
+        format!(
+            "Fields that have to be set before calling `build()`: {}.",
+            required.join(", ")
+        )
+    };
+    let build_doc = format!("Builds the [`{}`] from the fields set so far.", target_name);
+    let builder_fn_doc = format!("Returns an empty builder for [`{}`].", target_name);
+    let error_doc = format!("Error returned by `build()` of [`{}`].", builder_ident);
+
+    // std::error::Error isn't available without std
+    let error_impl = if struct_attrs.no_std {
+        quote!()
+    } else {
+        quote! {
+            impl ::std::error::Error for #error_ident {}
+        }
+    };
+
     let expanded = quote! {
-        pub struct #builder_ident {
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error_ident {
+            /// A required field was never set on the builder.
+            MissingField(&'static str),
+            /// A value was rejected during validation.
+            ValidationError {
+                /// The field whose value was rejected, if it wasn't the builder as a whole.
+                field: #core::option::Option<&'static str>,
+                /// Why the value was rejected.
+                message: #alloc::string::String,
+            },
+            /// The builder of a nested field failed.
+            SubBuilderError {
+                /// The field configured through the nested builder.
+                field: &'static str,
+                /// The error of the nested builder.
+                message: #alloc::string::String,
+            },
+            /// The initialiser of an unset field failed.
+            DefaultError {
+                /// The field whose initialiser failed.
+                field: &'static str,
+                /// The error of the initialiser.
+                message: #alloc::string::String,
+            },
+        }
+
+        impl #core::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                match self {
+                    #error_ident::MissingField(field) => {
+                        #core::write!(f, "missing required field `{}`", field)
+                    }
+                    #error_ident::ValidationError {
+                        field: #core::option::Option::Some(field),
+                        message,
+                    } => #core::write!(f, "invalid value for field `{}`: {}", field, message),
+                    #error_ident::ValidationError { field: #core::option::Option::None, message } => {
+                        #core::write!(f, "validation failed: {}", message)
+                    }
+                    #error_ident::SubBuilderError { field, message } => {
+                        #core::write!(f, "in field `{}`: {}", field, message)
+                    }
+                    #error_ident::DefaultError { field, message } => {
+                        #core::write!(f, "could not initialise field `{}`: {}", field, message)
+                    }
+                }
+            }
+        }
+
+        #error_impl
+
+        #[doc = #builder_doc]
+        #[doc = ""]
+        #[doc = #required_doc]
+        #builder_attrs
+        #serde_default
+        #vis struct #builder_ident #builder_generics #where_clause {
             #(#builder_fields,)*
+            #phantom_decl
         }
 
-        impl #builder_ident {
+        impl #state_impl_generics #builder_ident #state_ty_generics #where_clause {
             #(#builder_methods)*
+            #merge_method
+            #deserialize_methods
+        }
 
-            pub fn build(&mut self) -> std::result::Result<#input_ident, std::boxed::Box<dyn std::error::Error>> {
-                Ok(Command {
+        #default_impl
+
+        impl #impl_generics #set_builder_ty #where_clause {
+            #[doc = #build_doc]
+            ///
+            /// Fails if a required field was never set or a value doesn't pass validation.
+            #vis #build_async fn build(#build_receiver) -> #core::result::Result<#input_ident #ty_generics, #build_error> {
+                #(#field_validations)*
+                #struct_validation
+                #struct_default
+                #core::result::Result::Ok(#ctor {
                     #(#input_fields,)*
                 })
             }
+
+            #from_target_method
         }
 
-        impl #input_ident {
-            pub fn builder() -> #builder_ident {
+        impl #impl_generics #input_ident #ty_generics #where_clause {
+            #[doc = #builder_fn_doc]
+            #vis fn #builder_fn() -> #unset_builder_ty {
                 #builder_ident {
                     #(#builder_empty_fields,)*
+                    #phantom_init
                 }
             }
         }
     };
 
-    TokenStream::from(expanded)
+    Ok(expanded)
+}
+
+// wraps the statements updating the builder into a setter using the given pattern; the
+// statements have to refer to the builder through setter_receiver(pattern)
+fn setter(
+    core: &proc_macro2::TokenStream,
+    pattern: Pattern,
+    vis: &syn::Visibility,
+    name: &syn::Ident,
+    generics: proc_macro2::TokenStream,
+    args: proc_macro2::TokenStream,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match pattern {
+        Pattern::Owned => quote! {
+            #vis fn #name #generics(mut self, #args) -> Self {
+                #body
+                self
+            }
+        },
+        Pattern::Mutable => quote! {
+            #vis fn #name #generics(&mut self, #args) -> &mut Self {
+                #body
+                self
+            }
+        },
+        Pattern::Immutable => quote! {
+            #vis fn #name #generics(&self, #args) -> Self {
+                let mut __next = #core::clone::Clone::clone(self);
+                #body
+                __next
+            }
+        },
+    }
+}
+
+// how the statements passed to setter() refer to the builder
+fn setter_receiver(pattern: Pattern) -> proc_macro2::TokenStream {
+    match pattern {
+        Pattern::Owned | Pattern::Mutable => quote!(self),
+        Pattern::Immutable => quote!(__next),
+    }
+}
+
+// a field that build() cannot do without
+fn is_required(field: &syn::Field, attrs: &FieldAttrs, struct_attrs: &StructAttrs) -> bool {
+    get_inner_ty(&field.ty, "Option").is_none()
+        && attrs.each.is_none()
+        && attrs.sub_builder.is_none()
+        && attrs.default.is_none()
+        && attrs.default_with.is_none()
+        && !struct_attrs.default
+}
+
+// Deserialize and Serialize in #[builder(derive(...))] are taken from serde
+fn is_serde_trait(path: &syn::Path) -> bool {
+    path.is_ident("Deserialize") || path.is_ident("Serialize")
 }
 
-// Check if type is outer_ty<T>, if it is, return T
+// the generic arguments matching a list of generic parameters, e.g. <'a, T, N> for <'a, T: Clone, const N: usize>
+fn generic_args(generics: &syn::Generics) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+    generics.params.iter().map(|param| match param {
+        syn::GenericParam::Lifetime(l) => {
+            let lifetime = &l.lifetime;
+            quote!(#lifetime)
+        }
+        syn::GenericParam::Type(t) => {
+            let ident = &t.ident;
+            quote!(#ident)
+        }
+        syn::GenericParam::Const(c) => {
+            let ident = &c.ident;
+            quote!(#ident)
+        }
+    })
+}
+
+// spell out the builder type with the given generic arguments
+fn builder_ty(
+    builder_ident: &syn::Ident,
+    args: impl Iterator<Item = proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let args = args.collect::<Vec<_>>();
+    if args.is_empty() {
+        quote!(#builder_ident)
+    } else {
+        quote!(#builder_ident<#(#args),*>)
+    }
+}
+
+// CamelCase to snake_case, for naming builder functions after enum variants
+fn to_snake_case(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            // a word starts after a lowercase letter or digit, or at the last capital of a run
+            // of them, so an acronym like the `HTTP` in `HTTPRequest` stays one word
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if !prev.is_uppercase() || next_lower {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+// the builder generated for a field's type, e.g. config::EnvBuilder<T> for config::Env<T>
+fn sub_builder_ty(ty: &syn::Type, span: proc_macro2::Span) -> syn::Result<syn::Type> {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let mut path = path.clone();
+            let last = path.segments.last_mut().unwrap();
+            last.ident = format_ident!("{}Builder", last.ident, span = span);
+            Ok(syn::Type::Path(syn::TypePath { qself: None, path }))
+        }
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            "`builder(sub_builder)` requires a field whose type derives Builder",
+        )),
+    }
+}
+
+// Check if type is outer_ty<T>, if it is, return T. Paths like std::option::Option<T> count too,
+// since callers who redefine prelude names have to spell them out that way.
 fn get_inner_ty(ty: &syn::Type, outer_ty: &str) -> Option<syn::Type> {
+    // references, arrays, tuples etc. can never be outer_ty<T>
     let segments = if let syn::Type::Path(syn::TypePath {
         path: syn::Path { ref segments, .. },
         ..
@@ -152,75 +1262,564 @@ fn get_inner_ty(ty: &syn::Type, outer_ty: &str) -> Option<syn::Type> {
     {
         segments
     } else {
-        panic!("could not fetch segments!");
+        return None;
     };
 
-    if segments.first().unwrap().ident.ne(outer_ty) {
+    if segments.last().unwrap().ident.ne(outer_ty) {
         return None;
     }
 
-    match segments.first().unwrap().clone().arguments {
+    match segments.last().unwrap().clone().arguments {
         syn::PathArguments::AngleBracketed(s) => match s.args.first().unwrap() {
-            syn::GenericArgument::Type(t) => return Some(t.clone()),
-            _ => return None,
+            syn::GenericArgument::Type(t) => Some(t.clone()),
+            _ => None,
         },
-        _ => return None,
+        _ => None,
     }
 }
 
-// returns true if we see something that MIGHT be an each field (includes ill-formatted fields)
-fn has_each_attr(field: &syn::Field) -> bool {
-    match get_each_attr(field) {
-        Ok(s) => return s.is_some(),
-        Err(_) => return true,
-    }
+// options given through #[builder(...)] on the struct itself
+#[derive(Default)]
+struct StructAttrs {
+    // custom error type for build(), from #[builder(build_fn(error = "..."))]
+    error: Option<syn::Path>,
+    // check run against the whole builder by build(), from #[builder(build_fn(validate = "..."))]
+    validate: Option<syn::Path>,
+    // build() is an async fn, from #[builder(build_fn(async))]
+    is_async: bool,
+    // generated code only uses core and alloc, from #[builder(no_std)]
+    no_std: bool,
+    // fall back to the struct's Default impl for unset fields, from #[builder(default)]
+    default: bool,
+    // track required fields in the builder's type, from #[builder(typestate)]
+    typestate: bool,
+    // how setters and build() take the builder, from #[builder(pattern = "...")]
+    pattern: Pattern,
+    // every setter accepts anything Into the field's type, from #[builder(setter(into))]
+    into: bool,
+    // every setter gets a fallible twin, from #[builder(setter(try_into))]
+    try_into: bool,
+    // put in front of every setter's name, from #[builder(setter(prefix = "..."))]
+    prefix: Option<String>,
+    // traits derived on the builder, from #[builder(derive(...))]; Deserialize and Serialize
+    // refer to serde's
+    derives: Vec<syn::Path>,
+    // passed on to the builder as #[serde(...)], from #[builder(serde(...))]
+    serde: Vec<proc_macro2::TokenStream>,
+    // passed on to the builder as is, from #[builder(attrs(...))]
+    attrs: Vec<syn::NestedMeta>,
+    // visibility of the builder and everything on it, from #[builder(vis = "...")]
+    vis: Option<syn::Visibility>,
+    // name of the builder, from #[builder(name = "...")]
+    name: Option<syn::Ident>,
 }
 
-fn get_each_attr(
-    field: &syn::Field,
-) -> Result<Option<std::string::String>, proc_macro2::TokenStream> {
-    fn make_comp_error<T: quote::ToTokens>(
-        t: T,
-    ) -> Result<Option<std::string::String>, proc_macro2::TokenStream> {
-        Err(syn::Error::new_spanned(t, "expected `builder(each = \"...\")`").to_compile_error())
-    }
+#[derive(Clone, Copy, PartialEq, Default)]
+enum Pattern {
+    // setters and build() consume the builder, so no field has to be Clone
+    Owned,
+    // setters take and return &mut Self, build() clones the fields
+    #[default]
+    Mutable,
+    // setters return an updated clone of the builder, build() clones the fields
+    Immutable,
+}
+
+fn get_struct_attrs(attrs: &[syn::Attribute]) -> syn::Result<StructAttrs> {
+    const OPTIONS: &[&str] = &[
+        "default",
+        "typestate",
+        "no_std",
+        "pattern",
+        "vis",
+        "name",
+        "setter",
+        "build_fn",
+        "derive",
+        "serde",
+        "attrs",
+    ];
+    const SETTER_OPTIONS: &[&str] = &["into", "try_into", "prefix"];
+    const BUILD_FN_OPTIONS: &[&str] = &["error", "validate", "async"];
+
+    let mut struct_attrs = StructAttrs::default();
+    let mut seen = Vec::new();
+    let mut pattern_lit = None;
 
-    if field.attrs.is_empty() {
-        return Ok(None);
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("builder")) {
+        for nested in &builder_options(attr)? {
+            let (key, meta) = option_name(nested, None, OPTIONS, &mut seen)?;
+            match key.as_str() {
+                "default" => struct_attrs.default = flag(&key, meta)?,
+                "typestate" => struct_attrs.typestate = flag(&key, meta)?,
+                "no_std" => struct_attrs.no_std = flag(&key, meta)?,
+                "pattern" => {
+                    let s = str_value(&key, meta)?;
+                    struct_attrs.pattern = match s.value().as_str() {
+                        "owned" => Pattern::Owned,
+                        "mutable" => Pattern::Mutable,
+                        "immutable" => Pattern::Immutable,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                s,
+                                "expected `owned`, `mutable` or `immutable`",
+                            ))
+                        }
+                    };
+                    pattern_lit = Some(s.clone());
+                }
+                "vis" => struct_attrs.vis = Some(str_value(&key, meta)?.parse()?),
+                "name" => struct_attrs.name = Some(str_value(&key, meta)?.parse()?),
+                "setter" => {
+                    for nested in list(&key, meta)? {
+                        let (key, meta) =
+                            option_name(nested, Some("setter"), SETTER_OPTIONS, &mut seen)?;
+                        match key.as_str() {
+                            "into" => struct_attrs.into = flag(&key, meta)?,
+                            "try_into" => struct_attrs.try_into = flag(&key, meta)?,
+                            _ => struct_attrs.prefix = Some(str_value(&key, meta)?.value()),
+                        }
+                    }
+                }
+                "build_fn" => {
+                    for nested in list(&key, meta)? {
+                        let (key, meta) =
+                            option_name(nested, Some("build_fn"), BUILD_FN_OPTIONS, &mut seen)?;
+                        match key.as_str() {
+                            "error" => struct_attrs.error = Some(str_value(&key, meta)?.parse()?),
+                            "validate" => {
+                                struct_attrs.validate = Some(str_value(&key, meta)?.parse()?)
+                            }
+                            _ => struct_attrs.is_async = flag(&key, meta)?,
+                        }
+                    }
+                }
+                "derive" => {
+                    for nested in list(&key, meta)? {
+                        let path = match nested {
+                            syn::NestedMeta::Meta(syn::Meta::Path(path)) => path,
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    nested,
+                                    "expected the name of a trait to derive",
+                                ))
+                            }
+                        };
+                        if is_serde_trait(path) && !cfg!(feature = "serde") {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                "deriving serde traits on the builder requires the `serde` feature of derive_builder",
+                            ));
+                        }
+                        struct_attrs.derives.push(path.clone());
+                    }
+                }
+                "serde" => {
+                    let nested = list(&key, meta)?;
+                    struct_attrs.serde.push(quote!(#nested));
+                }
+                _ => struct_attrs.attrs.extend(list(&key, meta)?.iter().cloned()),
+            }
+        }
     }
 
-    for attr in &field.attrs {
-        let meta = match attr.parse_meta() {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
+    // typestate setters change the builder's type, so they can only ever consume it
+    if struct_attrs.typestate {
+        match pattern_lit {
+            Some(ref lit) if struct_attrs.pattern != Pattern::Owned => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "typestate builders always use the `owned` pattern",
+                ));
+            }
+            _ => struct_attrs.pattern = Pattern::Owned,
+        }
 
-        let meta_list = match meta {
-            syn::Meta::List(ml) => ml,
-            _ => continue,
-        };
+        // a deserialized builder would have to pick its type before seeing which fields are set
+        if let Some(path) = struct_attrs.derives.iter().find(|d| is_serde_trait(d)) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "typestate builders cannot derive serde traits",
+            ));
+        }
+    }
 
-        if meta_list.path.segments.first().unwrap().ident.ne("builder") {
-            continue;
+    // an async build() consumes the builder, so that its future doesn't borrow from a builder
+    // that is typically a temporary; setters have to hand the builder on by value for that
+    if struct_attrs.is_async {
+        match pattern_lit {
+            Some(ref lit) if struct_attrs.pattern != Pattern::Owned => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "async builders always use the `owned` pattern",
+                ));
+            }
+            _ => struct_attrs.pattern = Pattern::Owned,
         }
+    }
 
-        let x = meta_list.nested.first().unwrap();
+    Ok(struct_attrs)
+}
 
-        match x {
-            syn::NestedMeta::Meta(syn::Meta::NameValue(m)) => {
-                if m.path.segments.first().unwrap().ident.ne("each") {
-                    return make_comp_error(meta_list);
-                }
+// a field of the target along with what we know about it
+struct BuilderField<'a> {
+    field: &'a syn::Field,
+    // name of the field on the builder side, used for its storage
+    ident: syn::Ident,
+    // name of the setter taking the whole value
+    setter: syn::Ident,
+    // name of the one-at-a-time setter of a collection
+    each: Option<syn::Ident>,
+    // name of the setter adding many items to a collection at once
+    extend: Option<syn::Ident>,
+    // name of the setter converting its argument with TryInto
+    try_setter: Option<syn::Ident>,
+    // how the field is referred to when constructing the target
+    member: syn::Member,
+    attrs: FieldAttrs,
+    // type parameter tracking whether the field has been set, in typestate mode
+    state: Option<syn::Ident>,
+    // builder type of a field built through a nested builder
+    sub_builder: Option<syn::Type>,
+}
 
-                if let syn::Lit::Str(ref s) = m.lit {
-                    return Ok(Some(s.value()));
-                } else {
-                    return make_comp_error(meta_list);
+// options given through #[builder(...)] on a single field
+#[derive(Default)]
+struct FieldAttrs {
+    // name of the field on the builder side, from #[builder(name = "...")]
+    name: Option<syn::Ident>,
+    // name of the one-at-a-time setter for a collection, from #[builder(each = "...")]
+    each: Option<syn::Ident>,
+    // value used by build() when the field was never set, from #[builder(default)] or
+    // #[builder(default = "...")]
+    default: Option<syn::Expr>,
+    // fallible initialiser called by build() when the field was never set, from
+    // #[builder(default_with = "...")] or #[builder(default_with_async = "...")]
+    default_with: Option<syn::Path>,
+    // the initialiser is an async fn, from #[builder(default_with_async = "...")]
+    default_with_async: bool,
+    // check run by build() on the value given to the setter, from #[builder(validate = "...")]
+    validate: Option<syn::Path>,
+    // field is configured through its own builder, from #[builder(sub_builder)]; kept for its
+    // span, which everything touching the nested builder is reported at
+    sub_builder: Option<syn::Path>,
+    // setter accepts anything Into the field's type, from #[builder(setter(into))]
+    into: bool,
+    // a try_ setter accepts anything TryInto the field's type, from
+    // #[builder(setter(try_into))]
+    try_into: bool,
+    // setter of an Option<T> field takes the whole Option, from
+    // #[builder(setter(strip_option = false))]
+    keep_option: bool,
+    // name of the setter, from #[builder(setter(name = "..."))]
+    setter_name: Option<syn::Ident>,
+    // put in front of the setters' names, from #[builder(setter(prefix = "..."))]
+    prefix: Option<String>,
+    // no setter at all, from #[builder(setter(skip))]; kept for its span
+    skip: Option<syn::Path>,
+    // passed on to the builder's field as #[serde(...)], from #[builder(serde(...))]
+    serde: Vec<proc_macro2::TokenStream>,
+    // passed on to the builder's field as is, from #[builder(attrs(...))]
+    attrs: Vec<syn::NestedMeta>,
+    // merge() replaces the collection instead of appending to it, from
+    // #[builder(merge = "replace")]
+    merge_replace: bool,
+}
+
+fn get_field_attrs(field: &syn::Field, core: &proc_macro2::TokenStream) -> syn::Result<FieldAttrs> {
+    const OPTIONS: &[&str] = &[
+        "each",
+        "name",
+        "default",
+        "default_with",
+        "default_with_async",
+        "validate",
+        "merge",
+        "sub_builder",
+        "setter",
+        "serde",
+        "attrs",
+    ];
+    const SETTER_OPTIONS: &[&str] = &["into", "try_into", "strip_option", "name", "prefix", "skip"];
+    // pairs of options that make no sense together
+    const CONFLICTS: &[(&str, &str)] = &[
+        ("default", "default_with"),
+        ("default", "default_with_async"),
+        ("default_with", "default_with_async"),
+        ("each", "default"),
+        ("each", "default_with"),
+        ("each", "default_with_async"),
+        ("each", "sub_builder"),
+        ("each", "setter(try_into)"),
+        ("sub_builder", "setter(into)"),
+        ("sub_builder", "setter(try_into)"),
+        ("sub_builder", "setter(strip_option)"),
+        ("setter(skip)", "setter(into)"),
+        ("setter(skip)", "setter(try_into)"),
+        ("setter(skip)", "setter(strip_option)"),
+        ("setter(skip)", "setter(name)"),
+        ("setter(skip)", "setter(prefix)"),
+    ];
+
+    let mut field_attrs = FieldAttrs::default();
+    let mut seen = Vec::new();
+    let mut merge_lit = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("builder"))
+    {
+        for nested in &builder_options(attr)? {
+            let (key, meta) = option_name(nested, None, OPTIONS, &mut seen)?;
+            match key.as_str() {
+                "each" => field_attrs.each = Some(str_value(&key, meta)?.parse()?),
+                "name" => field_attrs.name = Some(str_value(&key, meta)?.parse()?),
+                // a bare `default` falls back to the field type's Default impl
+                "default" => {
+                    field_attrs.default = match meta {
+                        syn::Meta::Path(_) => {
+                            Some(syn::parse_quote!(#core::default::Default::default()))
+                        }
+                        _ => Some(str_value(&key, meta)?.parse()?),
+                    }
+                }
+                "default_with" => field_attrs.default_with = Some(str_value(&key, meta)?.parse()?),
+                "default_with_async" => {
+                    field_attrs.default_with = Some(str_value(&key, meta)?.parse()?);
+                    field_attrs.default_with_async = true;
+                }
+                "validate" => field_attrs.validate = Some(str_value(&key, meta)?.parse()?),
+                "merge" => {
+                    let s = str_value(&key, meta)?;
+                    field_attrs.merge_replace = match s.value().as_str() {
+                        "append" => false,
+                        "replace" => true,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                s,
+                                "expected `append` or `replace`",
+                            ))
+                        }
+                    };
+                    merge_lit = Some(s.clone());
+                }
+                "sub_builder" => {
+                    flag(&key, meta)?;
+                    field_attrs.sub_builder = Some(meta.path().clone());
+                }
+                "setter" => {
+                    for nested in list(&key, meta)? {
+                        let (key, meta) =
+                            option_name(nested, Some("setter"), SETTER_OPTIONS, &mut seen)?;
+                        match key.as_str() {
+                            "into" => field_attrs.into = flag(&key, meta)?,
+                            "try_into" => field_attrs.try_into = flag(&key, meta)?,
+                            "strip_option" => field_attrs.keep_option = !bool_value(&key, meta)?,
+                            "name" => {
+                                field_attrs.setter_name = Some(str_value(&key, meta)?.parse()?)
+                            }
+                            "prefix" => field_attrs.prefix = Some(str_value(&key, meta)?.value()),
+                            _ => {
+                                flag(&key, meta)?;
+                                field_attrs.skip = Some(meta.path().clone());
+                            }
+                        }
+                    }
                 }
+                "serde" => {
+                    let nested = list(&key, meta)?;
+                    field_attrs.serde.push(quote!(#nested));
+                }
+                _ => field_attrs.attrs.extend(list(&key, meta)?.iter().cloned()),
             }
-            _ => return make_comp_error(meta_list),
         }
     }
 
-    Ok(None)
+    check_conflicts(&seen, CONFLICTS)?;
+
+    // every other field is simply overridden when set
+    if let (Some(lit), None) = (merge_lit, &field_attrs.each) {
+        return Err(syn::Error::new_spanned(
+            lit,
+            "`merge` only applies to collections with an `each` setter",
+        ));
+    }
+
+    Ok(field_attrs)
+}
+
+// options that may be given more than once, each time adding to the ones before; for
+// setter(...) and build_fn(...) the options inside them are still checked for duplicates
+const REPEATABLE: &[&str] = &["derive", "serde", "attrs", "setter", "build_fn"];
+
+// the options inside #[builder(...)]
+fn builder_options(
+    attr: &syn::Attribute,
+) -> syn::Result<syn::punctuated::Punctuated<syn::NestedMeta, syn::Token![,]>> {
+    match attr.parse_meta()? {
+        syn::Meta::List(ml) => Ok(ml.nested),
+        meta => Err(syn::Error::new_spanned(meta, "expected `builder(...)`")),
+    }
+}
+
+// The name of a single option, checked against the ones known where it appears: directly inside
+// #[builder(...)] or inside one of its lists like setter(...). Every option is recorded in `seen`
+// under its full name, e.g. `setter(into)`, along with where it was given.
+fn option_name<'a>(
+    nested: &'a syn::NestedMeta,
+    list: Option<&str>,
+    known: &[&str],
+    seen: &mut Vec<(String, proc_macro2::Span)>,
+) -> syn::Result<(String, &'a syn::Meta)> {
+    let expected = || {
+        let known = known
+            .iter()
+            .map(|k| format!("`{}`", k))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("expected one of {}", known)
+    };
+
+    let meta = match nested {
+        syn::NestedMeta::Meta(meta) => meta,
+        syn::NestedMeta::Lit(lit) => return Err(syn::Error::new_spanned(lit, expected())),
+    };
+    let ident = match meta.path().get_ident() {
+        Some(ident) => ident,
+        None => return Err(syn::Error::new_spanned(meta.path(), expected())),
+    };
+
+    let key = ident.to_string();
+    let full_name = match list {
+        Some(list) => format!("{}({})", list, key),
+        None => key.clone(),
+    };
+    if !known.contains(&key.as_str()) {
+        let message = match did_you_mean(&key, known) {
+            Some(known) => format!("unknown option `{}`, did you mean `{}`?", full_name, known),
+            None => format!("unknown option `{}`, {}", full_name, expected()),
+        };
+        return Err(syn::Error::new(ident.span(), message));
+    }
+    let repeatable = list.is_none() && REPEATABLE.contains(&key.as_str());
+    if !repeatable && seen.iter().any(|(name, _)| *name == full_name) {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("duplicate option `{}`", full_name),
+        ));
+    }
+    seen.push((full_name, ident.span()));
+
+    Ok((key, meta))
+}
+
+// the known option closest to an unknown one, if it is close enough to be a typo
+fn did_you_mean<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|k| (edit_distance(name, k), *k))
+        .filter(|&(distance, k)| distance <= std::cmp::max(1, k.len() / 3))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, k)| k)
+}
+
+// number of single character insertions, deletions and substitutions turning a into b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(row[j]).min(above)
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+// reports the later one of two options that were both given but can't be used together
+fn check_conflicts(
+    seen: &[(String, proc_macro2::Span)],
+    conflicts: &[(&str, &str)],
+) -> syn::Result<()> {
+    for (a, b) in conflicts {
+        let a = seen.iter().position(|(name, _)| name == a);
+        let b = seen.iter().position(|(name, _)| name == b);
+        if let (Some(a), Some(b)) = (a, b) {
+            let (earlier, later) = (&seen[a.min(b)], &seen[a.max(b)]);
+            return Err(syn::Error::new(
+                later.1,
+                format!("`{}` cannot be combined with `{}`", later.0, earlier.0),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// an option without a value, like `default`
+fn flag(key: &str, meta: &syn::Meta) -> syn::Result<bool> {
+    match meta {
+        syn::Meta::Path(_) => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!("expected `{}` without a value", key),
+        )),
+    }
+}
+
+// the value of an option like `each = "..."`
+fn str_value<'a>(key: &str, meta: &'a syn::Meta) -> syn::Result<&'a syn::LitStr> {
+    match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Ok(s),
+        syn::Meta::NameValue(nv) => Err(syn::Error::new_spanned(
+            &nv.lit,
+            format!("expected a string, as in `{} = \"...\"`", key),
+        )),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!("expected `{} = \"...\"`", key),
+        )),
+    }
+}
+
+// the value of an option like `strip_option = false`
+fn bool_value(key: &str, meta: &syn::Meta) -> syn::Result<bool> {
+    match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Bool(b),
+            ..
+        }) => Ok(b.value),
+        syn::Meta::NameValue(nv) => Err(syn::Error::new_spanned(
+            &nv.lit,
+            format!("expected `true` or `false`, as in `{} = false`", key),
+        )),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!("expected `{} = true` or `{} = false`", key, key),
+        )),
+    }
+}
+
+// the contents of an option like `setter(...)`
+fn list<'a>(
+    key: &str,
+    meta: &'a syn::Meta,
+) -> syn::Result<&'a syn::punctuated::Punctuated<syn::NestedMeta, syn::Token![,]>> {
+    match meta {
+        syn::Meta::List(ml) => Ok(&ml.nested),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!("expected `{}(...)`", key),
+        )),
+    }
 }
@@ -0,0 +1,50 @@
+// The builder should work for structs that are generic over lifetimes, types
+// and consts, including where-clauses and defaulted type parameters. All of
+// the input's generics need to be carried over to the builder struct, to its
+// impl block, and to the `builder` function.
+//
+//     pub struct CommandBuilder<'a, T, const N: usize, U: Clone = String>
+//     where
+//         T: Clone,
+//     {
+//         ...
+//     }
+//
+//     impl<'a, T, const N: usize, U: Clone> CommandBuilder<'a, T, N, U>
+//     where
+//         T: Clone,
+//     {
+//         ...
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command<'a, T, const N: usize, U: Clone = String>
+where
+    T: Clone,
+{
+    executable: &'a str,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+    flags: [u8; N],
+    current_dir: Option<U>,
+}
+
+fn main() {
+    let executable = String::from("cargo");
+
+    let command: Command<&str, 2> = Command::builder()
+        .executable(&executable)
+        .arg("build")
+        .arg("--release")
+        .flags([1, 2])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.flags, [1, 2]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
@@ -0,0 +1,46 @@
+// Instead of a boxed trait object, have `build` return an error enum generated
+// alongside the builder so that callers can tell which field was missing.
+//
+//     pub enum CommandBuilderError {
+//         MissingField(&'static str),
+//         ValidationError {
+//             field: Option<&'static str>,
+//             message: String,
+//         },
+//     }
+//
+// The enum implements Display and std::error::Error so that it still composes
+// with `?` in functions returning Box<dyn Error>.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn build_boxed() -> Result<Command, Box<dyn Error>> {
+    Ok(Command::builder().executable("cargo".to_owned()).build()?)
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(err, CommandBuilderError::MissingField("args"));
+    assert_eq!(err.to_string(), "missing required field `args`");
+
+    match Command::builder().build() {
+        Err(CommandBuilderError::MissingField(field)) => assert_eq!(field, "executable"),
+        _ => panic!("expected a missing field error"),
+    }
+
+    let err = build_boxed().err().unwrap();
+    assert_eq!(err.to_string(), "missing required field `args`");
+}
@@ -0,0 +1,41 @@
+// Callers who already have an error type of their own can have `build` return
+// it instead of the generated enum. The custom type has to be constructible
+// from the generated error enum.
+//
+//     #[builder(build_fn(error = "MyError"))]
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq)]
+pub enum MyError {
+    Incomplete(String),
+}
+
+impl From<CommandBuilderError> for MyError {
+    fn from(err: CommandBuilderError) -> Self {
+        MyError::Incomplete(err.to_string())
+    }
+}
+
+#[derive(Builder)]
+#[builder(build_fn(error = "MyError"))]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let result: Result<Command, MyError> = Command::builder().build();
+    assert_eq!(
+        result.err(),
+        Some(MyError::Incomplete(
+            "missing required field `executable`".to_owned()
+        )),
+    );
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+}
@@ -0,0 +1,83 @@
+// Fields that have a sensible fallback should not make `build` fail when the
+// caller never set them.
+//
+// A field marked #[builder(default)] falls back to Default::default(), and
+// #[builder(default = "...")] evaluates the given expression instead. Putting
+// #[builder(default)] on the struct itself takes every unset field from the
+// struct's own Default impl. A collection with an `each` setter counts as
+// unset as long as nothing was added to it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(default = "vec![\"PATH=/bin\".to_owned()]")]
+    env: Vec<String>,
+    #[builder(default = "Some(\"/tmp\".to_owned())")]
+    current_dir: Option<String>,
+}
+
+mod with_struct_default {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(default)]
+    pub struct Command {
+        pub executable: String,
+        #[builder(default = "1")]
+        pub jobs: usize,
+        pub current_dir: Option<String>,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+    }
+
+    impl Default for Command {
+        fn default() -> Self {
+            Command {
+                executable: "rustc".to_owned(),
+                jobs: 4,
+                current_dir: Some("..".to_owned()),
+                args: vec!["-v".to_owned()],
+            }
+        }
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.env, vec!["PATH=/bin"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/tmp"));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    assert_eq!(
+        Command::builder().build().err(),
+        Some(CommandBuilderError::MissingField("executable")),
+    );
+
+    let command = with_struct_default::Command::builder().build().unwrap();
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.jobs, 1);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.args, vec!["-v"]);
+
+    let command = with_struct_default::Command::builder()
+        .arg("-q".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["-q"]);
+}
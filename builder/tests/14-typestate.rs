@@ -0,0 +1,80 @@
+// With #[builder(typestate)] the builder keeps track of which required fields
+// have been set in its own type, so forgetting one is a compile error instead
+// of a MissingField error at runtime.
+//
+// Every required field gets a type parameter on the builder which is () until
+// the field's setter is called, and `build` only exists once all of them are
+// set:
+//
+//     pub struct CommandBuilder<__State0 = (), __State2 = ()> {
+//         executable: __State0,
+//         args: Vec<String>,
+//         current_dir: __State2,
+//         env: Option<String>,
+//     }
+//
+//     impl CommandBuilder<String, String> {
+//         pub fn build(self) -> Result<Command, CommandBuilderError> {
+//             ...
+//         }
+//     }
+//
+// Optional and repeated fields can be set in any state, and the setters may be
+// called in any order. The parameters are named after the field's position, so
+// fields whose names only differ in case never clash.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command<'a, T: Clone> {
+    executable: &'a str,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+    current_dir: String,
+    env: Option<String>,
+    #[builder(default = "4")]
+    jobs: usize,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+#[allow(non_snake_case)]
+pub struct Limits {
+    max_jobs: usize,
+    maxJobs: usize,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build")
+        .current_dir("..".to_owned())
+        .arg("--release")
+        .executable("cargo")
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, "..");
+    assert_eq!(command.env, None);
+    assert_eq!(command.jobs, 4);
+
+    // setters consume the builder, so partially built states can be named and
+    // passed around
+    let partial: CommandBuilder<&str, (), String> = Command::builder().current_dir("/".to_owned());
+    let command = partial
+        .executable("rustc")
+        .env("RUST_LOG=debug".to_owned())
+        .jobs(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.env.as_deref(), Some("RUST_LOG=debug"));
+    assert_eq!(command.jobs, 1);
+
+    let limits = Limits::builder().maxJobs(2).max_jobs(1).build().unwrap();
+    assert_eq!(limits.max_jobs, 1);
+    assert_eq!(limits.maxJobs, 2);
+}
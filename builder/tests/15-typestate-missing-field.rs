@@ -0,0 +1,19 @@
+// In typestate mode, calling `build` before every required field has been set
+// does not compile.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    current_dir: String,
+    env: Option<String>,
+}
+
+fn main() {
+    let _ = Command::builder()
+        .executable("cargo".to_owned())
+        .env("RUST_LOG=debug".to_owned())
+        .build();
+}
@@ -0,0 +1,47 @@
+// With #[builder(pattern = "owned")] the setters consume the builder and hand
+// it back, and `build` moves the fields out of it instead of cloning them. This
+// makes it possible to build structs holding values that are not Clone.
+//
+//     impl CommandBuilder {
+//         fn stdout(mut self, stdout: Sink) -> Self {
+//             self.stdout = Some(stdout);
+//             self
+//         }
+//
+//         pub fn build(self) -> Result<Command, CommandBuilderError> {
+//             ...
+//         }
+//     }
+
+use derive_builder::Builder;
+
+// Does not implement Clone.
+#[derive(Debug, PartialEq)]
+pub struct Sink(&'static str);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    stdout: Sink,
+    stderr: Option<Sink>,
+}
+
+fn main() {
+    let builder = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned());
+
+    let command = builder
+        .arg("--release".to_owned())
+        .stdout(Sink("out.log"))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.stdout, Sink("out.log"));
+    assert_eq!(command.stderr, None);
+}
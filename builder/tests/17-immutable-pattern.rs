@@ -0,0 +1,42 @@
+// With #[builder(pattern = "immutable")] the setters leave the builder they are
+// called on untouched and return an updated copy instead. A partially
+// configured builder can then serve as a template for several others.
+//
+//     impl CommandBuilder {
+//         fn executable(&self, executable: String) -> Self {
+//             let mut next = self.clone();
+//             next.executable = Some(executable);
+//             next
+//         }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let cargo = Command::builder().executable("cargo".to_owned());
+
+    let build = cargo.arg("build".to_owned()).build().unwrap();
+    let test = cargo
+        .arg("test".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    let bare = cargo.build().unwrap();
+
+    assert_eq!(build.args, vec!["build"]);
+    assert_eq!(build.current_dir, None);
+    assert_eq!(test.args, vec!["test"]);
+    assert_eq!(test.current_dir.as_deref(), Some(".."));
+    assert!(bare.args.is_empty());
+
+    assert!(Command::builder().build().is_err());
+}
@@ -0,0 +1,99 @@
+// Values can be checked when `build` is called. A field marked
+// #[builder(validate = "path")] has the given function called with a
+// reference to the value passed to its setter, and a struct marked
+// #[builder(build_fn(validate = "path"))] has the function called with a
+// reference to the whole builder. Either may return an error message, which
+// `build` reports as a ValidationError.
+//
+//     fn check_args(args: &Vec<String>) -> Result<(), String>;
+//
+//     fn check_command(builder: &CommandBuilder) -> Result<(), String>;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(validate = "check_command"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", validate = "check_args")]
+    args: Vec<String>,
+    #[builder(validate = "check_dir")]
+    current_dir: Option<String>,
+}
+
+fn check_args(args: &Vec<String>) -> Result<(), &'static str> {
+    if args.is_empty() {
+        Err("at least one argument is required")
+    } else {
+        Ok(())
+    }
+}
+
+fn check_dir(dir: &String) -> Result<(), String> {
+    if std::path::Path::new(dir).exists() {
+        Ok(())
+    } else {
+        Err(format!("{} does not exist", dir))
+    }
+}
+
+fn check_command(builder: &CommandBuilder) -> Result<(), String> {
+    match builder.executable {
+        Some(ref e) if e.contains(' ') => Err(format!("`{}` contains spaces", e)),
+        _ => Ok(()),
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir(".".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["build"]);
+
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::ValidationError {
+            field: Some("args"),
+            message: "at least one argument is required".to_owned(),
+        },
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid value for field `args`: at least one argument is required",
+    );
+
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("/does/not/exist".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value for field `current_dir`: /does/not/exist does not exist",
+    );
+
+    let err = Command::builder()
+        .executable("cargo build".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::ValidationError {
+            field: None,
+            message: "`cargo build` contains spaces".to_owned(),
+        },
+    );
+    assert_eq!(err.to_string(), "validation failed: `cargo build` contains spaces");
+}
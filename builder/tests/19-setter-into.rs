@@ -0,0 +1,86 @@
+// With #[builder(setter(into))] a setter accepts anything that converts into
+// the field's type, so callers don't have to convert string literals by hand.
+// The attribute can be put on individual fields or on the struct to apply to
+// every field.
+//
+//     fn executable<V: Into<String>>(&mut self, executable: V) -> &mut Self {
+//         self.executable = Some(executable.into());
+//         self
+//     }
+//
+// Setters for Option<T> fields normally take a plain T. With
+// #[builder(setter(strip_option = false))] they take the Option itself, for
+// callers that already have one at hand.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    args: Vec<String>,
+    #[builder(setter(into))]
+    current_dir: Option<PathBuf>,
+    #[builder(setter(strip_option = false))]
+    stdin: Option<String>,
+    #[builder(setter(into, strip_option = false))]
+    stdout: Option<String>,
+}
+
+mod all_into {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(setter(into))]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+        pub jobs: Option<u64>,
+    }
+
+    pub fn cargo_build() -> Command {
+        Command::builder()
+            .executable("cargo")
+            .arg("build".to_owned())
+            .jobs(4u32)
+            .build()
+            .unwrap()
+    }
+}
+
+fn main() {
+    let stdin = None;
+
+    let command = Command::builder()
+        .executable("cargo")
+        .args(vec!["build".to_owned()])
+        .current_dir("..")
+        .stdin(stdin)
+        .stdout("out.log".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir, Some(PathBuf::from("..")));
+    assert_eq!(command.stdin, None);
+    assert_eq!(command.stdout.as_deref(), Some("out.log"));
+
+    let command = Command::builder()
+        .executable(String::from("rustc"))
+        .args(vec![])
+        .stdin(Some("in.txt".to_owned()))
+        .stdout(None)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.stdin.as_deref(), Some("in.txt"));
+    assert_eq!(command.stdout, None);
+
+    let command = all_into::cargo_build();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.jobs, Some(4));
+}
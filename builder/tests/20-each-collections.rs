@@ -0,0 +1,96 @@
+// The #[builder(each = "...")] attribute is not limited to Vec. Any collection
+// that implements Default and Extend can be filled one item at a time, where
+// an item of a map is a (key, value) pair.
+//
+// Alongside the one-at-a-time setter, each such field also gets an
+// `extend_<field>` method that appends everything from an iterator.
+//
+//     fn env(&mut self, env: (String, String)) -> &mut Self;
+//
+//     fn extend_env<I>(&mut self, env: I) -> &mut Self
+//     where
+//         I: IntoIterator<Item = (String, String)>;
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+// A collection of our own, only implementing what the builder needs.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Flags(u32);
+
+impl IntoIterator for Flags {
+    type Item = u8;
+    type IntoIter = std::vec::IntoIter<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (0..32).filter(|bit| self.0 & 1 << bit != 0).collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl Extend<u8> for Flags {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for bit in iter {
+            self.0 |= 1 << bit;
+        }
+    }
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: VecDeque<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(each = "define")]
+    defines: BTreeMap<String, i32>,
+    #[builder(each = "feature")]
+    features: BTreeSet<String>,
+    #[builder(each = "target")]
+    targets: HashSet<&'static str>,
+    #[builder(each = "flag")]
+    flags: Flags,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .extend_args(vec!["--release".to_owned(), "--locked".to_owned()])
+        .env(("RUST_LOG".to_owned(), "debug".to_owned()))
+        .env(("RUST_BACKTRACE".to_owned(), "1".to_owned()))
+        .extend_defines(vec![("B".to_owned(), 2), ("A".to_owned(), 1)])
+        .feature("std".to_owned())
+        .feature("serde".to_owned())
+        .feature("std".to_owned())
+        .target("x86_64-unknown-linux-gnu")
+        .flag(0)
+        .extend_flags(2..4)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build", "--release", "--locked"]);
+    assert_eq!(command.env.len(), 2);
+    assert_eq!(command.env["RUST_LOG"], "debug");
+    assert_eq!(
+        command.defines.into_iter().collect::<Vec<_>>(),
+        vec![("A".to_owned(), 1), ("B".to_owned(), 2)],
+    );
+    assert_eq!(
+        command.features.into_iter().collect::<Vec<_>>(),
+        vec!["serde", "std"],
+    );
+    assert!(command.targets.contains("x86_64-unknown-linux-gnu"));
+    assert_eq!(command.flags, Flags(0b1101));
+
+    // The all-at-once setter still replaces the whole collection.
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .args(VecDeque::from(vec!["test".to_owned()]))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["test"]);
+    assert!(command.env.is_empty());
+}
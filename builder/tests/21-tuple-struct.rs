@@ -0,0 +1,36 @@
+// Tuple structs get positional setters named after the field's index, _0, _1,
+// and so on. A field can be given a more descriptive setter name with
+// #[builder(name = "...")].
+//
+//     impl CommandBuilder {
+//         fn _0(&mut self, _0: String) -> &mut Self;
+//         fn arg(&mut self, arg: String) -> &mut Self;
+//         fn current_dir(&mut self, current_dir: String) -> &mut Self;
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command(
+    String,
+    #[builder(each = "arg")] Vec<String>,
+    #[builder(name = "current_dir")] Option<String>,
+);
+
+fn main() {
+    let command = Command::builder()
+        ._0("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.0, "cargo");
+    assert_eq!(command.1, vec!["build"]);
+    assert_eq!(command.2.as_deref(), Some(".."));
+
+    assert_eq!(
+        Command::builder().build().err(),
+        Some(CommandBuilderError::MissingField("_0")),
+    );
+}
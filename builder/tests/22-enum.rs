@@ -0,0 +1,65 @@
+// Deriving Builder on an enum generates one builder per variant. The builder
+// for a variant is named after the enum and the variant, and is created by an
+// associated function named after the variant.
+//
+//     impl Shape {
+//         pub fn circle_builder() -> ShapeCircleBuilder { ... }
+//         pub fn rounded_rect_builder() -> ShapeRoundedRectBuilder { ... }
+//         pub fn empty_builder() -> ShapeEmptyBuilder { ... }
+//         pub fn svg_path_builder() -> ShapeSVGPathBuilder { ... }
+//     }
+//
+// A run of capitals like `SVG` counts as a single word.
+//
+// Field attributes work the same way inside of variants, and the variant
+// builders share the options given on the enum itself.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(setter(into))]
+pub enum Shape<T: Clone + Default> {
+    Circle {
+        radius: T,
+        #[builder(default)]
+        label: Option<String>,
+    },
+    RoundedRect(T, T, #[builder(name = "corner", default)] T),
+    Empty,
+    SVGPath {
+        data: String,
+    },
+}
+
+fn main() {
+    let circle = Shape::circle_builder().radius(1.5).build().unwrap();
+    assert_eq!(
+        circle,
+        Shape::Circle {
+            radius: 1.5,
+            label: None,
+        },
+    );
+
+    let rect = Shape::rounded_rect_builder()
+        ._0(2u8)
+        ._1(3u8)
+        .corner(1u8)
+        .build()
+        .unwrap();
+    assert_eq!(rect, Shape::RoundedRect(2, 3, 1));
+
+    let err = Shape::<u8>::rounded_rect_builder()._0(2u8).build().err();
+    assert_eq!(err, Some(ShapeRoundedRectBuilderError::MissingField("_1")));
+
+    let empty = Shape::<u8>::empty_builder().build().unwrap();
+    assert_eq!(empty, Shape::Empty);
+
+    let path = Shape::<u8>::svg_path_builder().data("M0 0").build().unwrap();
+    assert_eq!(
+        path,
+        Shape::SVGPath {
+            data: "M0 0".to_owned(),
+        },
+    );
+}
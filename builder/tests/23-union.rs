@@ -0,0 +1,12 @@
+// Unions have no notion of a complete value that a builder could check for,
+// so deriving Builder on one is reported as an error rather than a panic.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Command {
+    executable: u32,
+    jobs: u64,
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+// Nothing about the generated code should depend on the input being called
+// Command. Here several differently named structs derive Builder side by side
+// in the same module, and one of them holds values built by the others.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq, Clone)]
+pub struct Compiler {
+    executable: String,
+    #[builder(each = "flag")]
+    flags: Vec<String>,
+}
+
+#[derive(Builder, Debug, PartialEq, Clone)]
+pub struct Linker {
+    executable: String,
+    script: Option<String>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Toolchain {
+    compiler: Compiler,
+    linker: Linker,
+    #[builder(each = "target")]
+    targets: Vec<String>,
+}
+
+fn main() {
+    let compiler = Compiler::builder()
+        .executable("rustc".to_owned())
+        .flag("-O".to_owned())
+        .build()
+        .unwrap();
+
+    let linker = Linker::builder()
+        .executable("ld".to_owned())
+        .build()
+        .unwrap();
+
+    let toolchain = Toolchain::builder()
+        .compiler(compiler.clone())
+        .linker(linker.clone())
+        .target("x86_64-unknown-linux-gnu".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(toolchain.compiler, compiler);
+    assert_eq!(toolchain.linker, linker);
+    assert_eq!(toolchain.targets, vec!["x86_64-unknown-linux-gnu"]);
+
+    let err: ToolchainBuilderError = Toolchain::builder()
+        .compiler(compiler)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, ToolchainBuilderError::MissingField("linker"));
+
+    let err: LinkerBuilderError = Linker::builder().build().err().unwrap();
+    assert_eq!(err, LinkerBuilderError::MissingField("executable"));
+}
@@ -0,0 +1,170 @@
+// The same check as 09-redefined-prelude-types.rs, extended to every path the
+// generated code refers to across all of the builder's options: values such
+// as Ok and Some, traits such as Clone and Extend, and macros such as write!.
+// Even `std` itself may be shadowed by a local module of the same name.
+
+#![allow(dead_code, unused_macros, non_upper_case_globals, non_camel_case_types)]
+
+mod redefined {
+    use derive_builder::Builder;
+
+    type Option = ();
+    type Some = ();
+    type None = ();
+    type Result = ();
+    type Ok = ();
+    type Err = ();
+    type Box = ();
+    type String = ();
+    type Vec = ();
+    type PhantomData = ();
+
+    const Ok: () = ();
+    const Err: () = ();
+    const Some: () = ();
+    const None: () = ();
+
+    trait Clone {}
+    trait Default {}
+    trait From {}
+    trait Into {}
+    trait Extend {}
+    trait IntoIterator {}
+    trait Iterator {}
+
+    macro_rules! write {
+        () => {};
+    }
+
+    macro_rules! vec {
+        () => {};
+    }
+
+    #[derive(Builder)]
+    #[builder(setter(into), build_fn(validate = "check"))]
+    pub struct Command<'a, T: std::clone::Clone> {
+        pub executable: &'a str,
+        #[builder(each = "arg", validate = "non_empty")]
+        pub args: std::vec::Vec<T>,
+        #[builder(each = "env")]
+        pub env: std::collections::HashMap<std::string::String, std::string::String>,
+        pub current_dir: std::option::Option<std::string::String>,
+        #[builder(default = "4")]
+        pub jobs: usize,
+        #[builder(default)]
+        pub verbose: bool,
+    }
+
+    fn check<'a, T: std::clone::Clone>(
+        _: &CommandBuilder<'a, T>,
+    ) -> std::result::Result<(), &'static str> {
+        std::result::Result::Ok(())
+    }
+
+    fn non_empty<T>(args: &std::vec::Vec<T>) -> std::result::Result<(), &'static str> {
+        if args.is_empty() {
+            std::result::Result::Err("empty")
+        } else {
+            std::result::Result::Ok(())
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(pattern = "owned", default)]
+    pub struct Owned {
+        pub executable: std::string::String,
+    }
+
+    impl std::default::Default for Owned {
+        fn default() -> Self {
+            Owned {
+                executable: std::string::String::new(),
+            }
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(pattern = "immutable")]
+    pub struct Immutable(#[builder(each = "arg")] std::vec::Vec<u8>);
+
+    #[derive(Builder)]
+    #[builder(typestate)]
+    pub struct Typestate<T> {
+        pub required: T,
+        pub optional: std::option::Option<T>,
+    }
+
+    #[derive(Builder)]
+    pub enum Shape {
+        Circle { radius: f64 },
+        Empty,
+    }
+
+    pub fn run() {
+        let command = Command::builder()
+            .executable("cargo")
+            .arg(1)
+            .env(("A".into(), "B".into()))
+            .current_dir("..")
+            .build()
+            .unwrap();
+        assert_eq!(command.executable, "cargo");
+        assert_eq!(command.jobs, 4);
+        assert!(!command.verbose);
+
+        let err = Command::<u8>::builder().executable("cargo").build().err().unwrap();
+        assert_eq!(
+            std::string::ToString::to_string(&err),
+            "invalid value for field `args`: empty",
+        );
+
+        let owned = Owned::builder().build().unwrap();
+        assert_eq!(owned.executable, "");
+
+        let immutable = Immutable::builder().arg(1).build().unwrap();
+        assert_eq!(immutable.0, [1]);
+
+        let typestate = Typestate::builder().required(1).build().unwrap();
+        assert_eq!(typestate.required, 1);
+
+        let _ = Shape::circle_builder().radius(1.0).build().unwrap();
+        let _ = Shape::empty_builder().build().unwrap();
+    }
+}
+
+mod shadowed_std {
+    use derive_builder::Builder;
+
+    mod std {}
+
+    #[derive(Builder)]
+    #[builder(build_fn(validate = "check"))]
+    pub struct Command {
+        pub executable: ::std::string::String,
+        #[builder(each = "arg")]
+        pub args: ::std::vec::Vec<::std::string::String>,
+        #[builder(default)]
+        pub jobs: usize,
+    }
+
+    fn check(_: &CommandBuilder) -> ::std::result::Result<(), &'static str> {
+        ::std::result::Result::Ok(())
+    }
+
+    pub fn run() {
+        let command = Command::builder()
+            .executable("cargo".into())
+            .arg("build".into())
+            .build()
+            .unwrap();
+        assert_eq!(command.args, ["build"]);
+
+        let err = Command::builder().build().err().unwrap();
+        let _: &dyn ::std::error::Error = &err;
+    }
+}
+
+fn main() {
+    redefined::run();
+    shadowed_std::run();
+}
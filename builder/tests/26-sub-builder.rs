@@ -0,0 +1,103 @@
+// A field whose type also derives Builder can be configured through that
+// type's builder instead of being built separately and passed in whole. With
+// #[builder(sub_builder)] the outer builder exposes the inner builder, creating
+// it the first time it is asked for:
+//
+//     impl CommandBuilder {
+//         fn env(&mut self) -> &mut EnvConfigBuilder {
+//             self.env.get_or_insert_with(EnvConfig::builder)
+//         }
+//     }
+//
+// The outer `build` builds every nested builder, including ones that were
+// never touched, and reports their errors as a SubBuilderError naming the
+// field they came from.
+//
+// Since the nested builder is configured through a `&mut` reference, built by
+// reference and merged with its own merge(), the field's type has to use a
+// plain builder: the default `mutable` pattern, no typestate and the default
+// name of `{Type}Builder`. Errors from any other kind of builder are reported
+// at the field's sub_builder option.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct EnvConfig {
+    #[builder(each = "var")]
+    vars: Vec<(String, String)>,
+    clear: Option<bool>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Mount {
+    source: String,
+    read_only: Option<bool>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct SandboxConfig {
+    root: String,
+    #[builder(sub_builder)]
+    mount: Mount,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder)]
+    env: EnvConfig,
+    #[builder(sub_builder)]
+    sandbox: SandboxConfig,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    name: String,
+    #[builder(sub_builder)]
+    env: EnvConfig,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    builder
+        .env()
+        .var(("RUST_LOG".to_owned(), "debug".to_owned()))
+        .clear(true);
+    builder.sandbox().root("/".to_owned());
+    builder.sandbox().mount().source("/tmp".to_owned());
+
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.env.vars, vec![("RUST_LOG".to_owned(), "debug".to_owned())]);
+    assert_eq!(command.env.clear, Some(true));
+    assert_eq!(command.sandbox.root, "/");
+    assert_eq!(command.sandbox.mount.source, "/tmp");
+
+    // Untouched nested builders are built empty, which works for EnvConfig but
+    // not for SandboxConfig.
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    let err = builder.build().err().unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::SubBuilderError {
+            field: "sandbox",
+            message: "missing required field `root`".to_owned(),
+        },
+    );
+
+    builder.sandbox().root("/".to_owned());
+    let err = builder.build().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "in field `sandbox`: in field `mount`: missing required field `source`",
+    );
+
+    let mut job = Job::builder().name("test".to_owned());
+    job.env().var(("CI".to_owned(), "1".to_owned()));
+    let job = job.build().unwrap();
+    assert_eq!(job.name, "test");
+    assert_eq!(job.env.vars.len(), 1);
+}
@@ -0,0 +1,81 @@
+// With the `serde` feature enabled, #[builder(derive(Deserialize, Serialize))]
+// derives serde's traits on the builder itself. Since every field of a
+// builder is optional, it can be read from a partial configuration file and
+// finished off in code, or the other way around: a builder configured in code
+// can have a document layered on top of it.
+//
+//     impl CommandBuilder {
+//         pub fn merge_deserialized<'de, D>(&mut self, deserializer: D)
+//             -> Result<&mut Self, D::Error>
+//         where
+//             D: serde::Deserializer<'de>,
+//         {
+//             ...
+//         }
+//     }
+//
+// Fields missing from the document are left alone, collections with an
+// `each` setter are appended to. Anything inside #[builder(serde(...))] is
+// passed on to the builder as a #[serde(...)] attribute, on fields as well as
+// on the struct.
+//
+// Serializing a builder writes out the fields that were set. Collections with
+// an `each` setter have no unset state, so they are always written, even when
+// empty.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(derive(Deserialize, Serialize), serde(deny_unknown_fields))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(serde(rename = "cwd"))]
+    current_dir: Option<String>,
+    #[builder(default = "10")]
+    retries: u32,
+}
+
+fn main() {
+    // a partial document on its own
+    let mut builder: CommandBuilder =
+        serde_json::from_str(r#"{ "args": ["build"], "cwd": ".." }"#).unwrap();
+    builder.executable("cargo".to_owned());
+    let command = builder.build().unwrap();
+    assert_eq!(
+        command,
+        Command {
+            executable: "cargo".to_owned(),
+            args: vec!["build".to_owned()],
+            current_dir: Some("..".to_owned()),
+            retries: 10,
+        }
+    );
+
+    // layered over a builder configured in code
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned()).retries(3);
+    let mut de = serde_json::Deserializer::from_str(r#"{ "executable": "xargo", "args": ["--release"] }"#);
+    builder.merge_deserialized(&mut de).unwrap();
+    let command = builder.build().unwrap();
+    assert_eq!(
+        command,
+        Command {
+            executable: "xargo".to_owned(),
+            args: vec!["build".to_owned(), "--release".to_owned()],
+            current_dir: None,
+            retries: 3,
+        }
+    );
+
+    // unknown keys are rejected through the passed-on #[serde(deny_unknown_fields)]
+    let mut de = serde_json::Deserializer::from_str(r#"{ "current_dir": ".." }"#);
+    assert!(builder.merge_deserialized(&mut de).is_err());
+
+    // only what was set ends up in the document, apart from `each` collections
+    let mut builder = Command::builder();
+    builder.current_dir("/tmp".to_owned());
+    let json = serde_json::to_string(&builder).unwrap();
+    assert_eq!(json, r#"{"args":[],"cwd":"/tmp"}"#);
+}
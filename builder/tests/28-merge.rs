@@ -0,0 +1,114 @@
+// Builders can be layered on top of each other, e.g. defaults, then a config
+// file, then environment variables, then command line flags:
+//
+//     impl CommandBuilder {
+//         pub fn merge(&mut self, other: Self) -> &mut Self {
+//             ...
+//         }
+//     }
+//
+// Every field that was set on `other` wins. Collections with an `each` setter
+// are appended to, or replaced as a whole when `other` holds anything if the
+// field says #[builder(merge = "replace")]. Nested builders are merged field
+// by field.
+//
+// The other direction is covered by `from_target`, which seeds a builder with
+// all the fields of an existing value so that a copy of it can be tweaked.
+// Owned builders take the value itself, all others take a reference and
+// clone the fields.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq, Clone)]
+pub struct Limits {
+    memory: Option<u64>,
+    files: Option<u64>,
+}
+
+#[derive(Builder, Debug, PartialEq, Clone)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env", merge = "replace")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    name: String,
+    #[builder(each = "step")]
+    steps: Vec<String>,
+}
+
+fn main() {
+    let mut defaults = Command::builder();
+    defaults
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env("RUST_LOG=info".to_owned());
+    defaults.limits().memory(1024);
+
+    let mut config = Command::builder();
+    config.current_dir("/src".to_owned()).arg("--release".to_owned());
+    config.limits().files(64);
+
+    let mut env = Command::builder();
+    env.env("RUST_LOG=debug".to_owned())
+        .env("RUST_BACKTRACE=1".to_owned());
+
+    let mut flags = Command::builder();
+    flags.executable("xargo".to_owned());
+    flags.limits().memory(2048);
+
+    let command = defaults
+        .merge(config)
+        .merge(env)
+        .merge(flags)
+        .build()
+        .unwrap();
+    assert_eq!(
+        command,
+        Command {
+            executable: "xargo".to_owned(),
+            args: vec!["build".to_owned(), "--release".to_owned()],
+            env: vec!["RUST_LOG=debug".to_owned(), "RUST_BACKTRACE=1".to_owned()],
+            current_dir: Some("/src".to_owned()),
+            limits: Limits {
+                memory: Some(2048),
+                files: Some(64),
+            },
+        }
+    );
+
+    // layering an empty builder changes nothing, not even replaced collections
+    let unchanged = CommandBuilder::from_target(&command)
+        .merge(Command::builder())
+        .build()
+        .unwrap();
+    assert_eq!(unchanged, command);
+
+    let mut tweaked = CommandBuilder::from_target(&command);
+    tweaked.arg("--verbose".to_owned()).current_dir("/tmp".to_owned());
+    tweaked.limits().files(128);
+    let tweaked = tweaked.build().unwrap();
+    assert_eq!(tweaked.args.len(), 3);
+    assert_eq!(tweaked.current_dir.as_deref(), Some("/tmp"));
+    assert_eq!(tweaked.limits.memory, Some(2048));
+    assert_eq!(tweaked.limits.files, Some(128));
+
+    let job = Job::builder()
+        .name("ci".to_owned())
+        .step("test".to_owned())
+        .build()
+        .unwrap();
+    let job = JobBuilder::from_target(job)
+        .step("deploy".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(job.steps, ["test", "deploy"]);
+}
@@ -0,0 +1,14 @@
+// Choosing between appending and replacing only makes sense for collections
+// built up through an `each` setter; every other field is replaced whenever
+// it was set on the builder being merged in.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(merge = "append")]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+// The builder and everything on it, including the setters, are as visible as
+// the input, so a builder for a `pub` struct can be used from other modules
+// and crates. A different visibility can be asked for with
+// #[builder(vis = "...")].
+//
+// The builder's name can be chosen with #[builder(name = "...")], its error
+// type is then named after it. #[builder(derive(...))] derives further traits
+// on the builder and #[builder(attrs(...))] passes any other attributes on to
+// it, or to one of its fields when used on a field.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug, PartialEq)]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+        pub current_dir: Option<String>,
+    }
+
+    #[derive(Builder, Debug)]
+    #[builder(vis = "pub(crate)", name = "JobSpec")]
+    #[builder(derive(Debug, Clone, PartialEq), attrs(must_use))]
+    pub struct Job {
+        pub name: String,
+        #[builder(attrs(doc = "How often the job is retried."))]
+        pub retries: Option<u32>,
+    }
+}
+
+use config::{Command, Job, JobSpec, JobSpecError};
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build"]);
+
+    let mut spec: JobSpec = Job::builder();
+    spec.name("nightly".to_owned());
+    let copy = spec.clone();
+    assert_eq!(spec, copy);
+    assert!(format!("{:?}", copy).contains("nightly"));
+
+    let job = spec.retries(3).build().unwrap();
+    assert_eq!(job.retries, Some(3));
+
+    let err = Job::builder().build().unwrap_err();
+    assert_eq!(err, JobSpecError::MissingField("name"));
+}
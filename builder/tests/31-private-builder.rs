@@ -0,0 +1,16 @@
+// With #[builder(vis = "...")] the builder can also be less visible than the
+// input, keeping it an implementation detail of the module that defines it.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(vis = "pub(self)")]
+    pub struct Command {
+        pub executable: String,
+    }
+}
+
+fn main() {
+    let _ = config::Command::builder();
+}
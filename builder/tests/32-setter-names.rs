@@ -0,0 +1,60 @@
+// Setter names can be changed without renaming the fields they set:
+//
+//   - #[builder(setter(prefix = "with_"))] on the struct puts a prefix in
+//     front of every setter, including the one-at-a-time and extend_ setters
+//     of collections. It can also be given on a single field.
+//
+//   - #[builder(setter(name = "..."))] on a field names its setter outright,
+//     ignoring any prefix.
+//
+//   - #[builder(setter(skip))] leaves a field without any setter at all, so
+//     build() always uses its default. Skipping a field that has no default
+//     is an error.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(setter(prefix = "with_"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(name = "in_dir"))]
+    current_dir: Option<String>,
+    #[builder(setter(prefix = "set_"))]
+    timeout: Option<u64>,
+    #[builder(default = "42", setter(skip))]
+    id: u64,
+    #[builder(default, setter(skip))]
+    cache: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .with_executable("cargo".to_owned())
+        .with_arg("build".to_owned())
+        .with_extend_args(vec!["--release".to_owned()])
+        .in_dir("..".to_owned())
+        .set_timeout(30)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        command,
+        Command {
+            executable: "cargo".to_owned(),
+            args: vec!["build".to_owned(), "--release".to_owned()],
+            current_dir: Some("..".to_owned()),
+            timeout: Some(30),
+            id: 42,
+            cache: Vec::new(),
+        }
+    );
+
+    let command = Command::builder()
+        .with_executable("cargo".to_owned())
+        .with_args(vec!["test".to_owned()])
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["test"]);
+}
@@ -0,0 +1,15 @@
+// Two setters with the same name can't both be generated. A collection whose
+// `each` setter takes over the field's own name simply goes without the setter
+// taking the whole collection, but naming a setter explicitly after another
+// one is an error pointing at the name that was asked for.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", setter(name = "arg"))]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// A field without a setter can only ever get its value from a default, so
+// skipping the setter of a required field is an error.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(setter(skip))]
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Options that aren't close enough to a known one to guess what was meant are
+// reported along with every option that is accepted in that place, here the
+// ones inside setter(...).
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(setter(optional))]
+    current_dir: Option<String>,
+}
+
+fn main() {}
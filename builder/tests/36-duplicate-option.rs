@@ -0,0 +1,22 @@
+// Giving the same option twice is an error, even when the two are spread over
+// separate #[builder(...)] attributes, since only one of them could take
+// effect.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    #[builder(each = "argument")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(setter(into))]
+    #[builder(setter(into, prefix = "with_"))]
+    path: String,
+}
+
+fn main() {}
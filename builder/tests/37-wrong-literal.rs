@@ -0,0 +1,13 @@
+// Options are checked for the kind of literal they take, pointing at the
+// offending literal. strip_option takes a bool rather than a string.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(setter(strip_option = "false"))]
+    current_dir: Option<String>,
+}
+
+fn main() {}
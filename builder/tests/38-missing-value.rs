@@ -0,0 +1,13 @@
+// An option that needs a value can't be given on its own, and the error shows
+// how it is spelled out.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each)]
+    args: Vec<String>,
+}
+
+fn main() {}
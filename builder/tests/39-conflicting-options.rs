@@ -0,0 +1,19 @@
+// Some options contradict each other. A field configured through a nested
+// builder has no setter taking a value that could accept Into conversions.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder, setter(into))]
+    env: Env,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Errors about an `each` setter point at the name it was given, here because
+// it clashes with the setter of another field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "executable")]
+    args: Vec<String>,
+}
+
+fn main() {}
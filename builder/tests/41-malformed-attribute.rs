@@ -0,0 +1,13 @@
+// The options always go inside builder(...), any other form of the attribute
+// is an error.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder = "each"]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Options on the struct itself are checked the same way as the ones on its
+// fields, including suggestions for likely typos.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(patern = "owned")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
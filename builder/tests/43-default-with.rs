@@ -0,0 +1,67 @@
+// Fields whose default is expensive or can fail, like opening a socket or
+// reading a file, can be given an initialiser that build() only calls when the
+// field was never set:
+//
+//     #[builder(default_with = "read_motd")]
+//     motd: String,
+//
+//     fn read_motd() -> Result<String, E> where E: Display
+//
+// A failing initialiser makes build() fail with a DefaultError naming the
+// field.
+
+use derive_builder::Builder;
+use std::cell::Cell;
+
+thread_local! {
+    static CALLS: Cell<u32> = Cell::new(0);
+}
+
+fn default_port() -> Result<u16, std::num::ParseIntError> {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    "8080".parse()
+}
+
+fn read_motd() -> Result<String, std::io::Error> {
+    std::fs::read_to_string("/this/file/does/not/exist")
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    host: String,
+    #[builder(default_with = "default_port")]
+    port: u16,
+    #[builder(default_with = "read_motd")]
+    motd: String,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port(443)
+        .motd("hello".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 443);
+    assert_eq!(CALLS.with(Cell::get), 0);
+
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .motd("hello".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(CALLS.with(Cell::get), 1);
+
+    let err = Server::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("could not initialise field `motd`: "));
+    match err {
+        ServerBuilderError::DefaultError { field, .. } => assert_eq!(field, "motd"),
+        err => panic!("unexpected error: {}", err),
+    }
+}
@@ -0,0 +1,85 @@
+// With #[builder(build_fn(async))] build() is an async fn. It consumes the
+// builder, so that the future it returns can be stored and awaited later
+// without borrowing a temporary builder, which is why async builders always
+// use the `owned` pattern:
+//
+//     impl ConnectionBuilder {
+//         pub async fn build(self) -> Result<Connection, ConnectionBuilderError> {
+//             ...
+//         }
+//     }
+//
+// Initialisers given through #[builder(default_with_async = "...")] are async
+// fns, awaited by build() for the fields that were never set, while plain
+// `default_with` initialisers keep working as they do for a synchronous
+// build(). Errors of either end up in the builder's error type.
+
+use derive_builder::Builder;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+async fn resolve() -> Result<String, String> {
+    Ok("127.0.0.1:80".to_owned())
+}
+
+async fn handshake() -> Result<Vec<u8>, String> {
+    Err("connection reset".to_owned())
+}
+
+fn default_port() -> Result<u16, String> {
+    Ok(80)
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(build_fn(async))]
+pub struct Connection {
+    #[builder(default_with_async = "resolve")]
+    address: String,
+    #[builder(default_with = "default_port")]
+    port: u16,
+    #[builder(default_with_async = "handshake")]
+    session: Vec<u8>,
+    timeout: Option<u64>,
+}
+
+fn main() {
+    let future = Connection::builder().session(vec![1, 2, 3]).build();
+    let connection = block_on(future).unwrap();
+    assert_eq!(
+        connection,
+        Connection {
+            address: "127.0.0.1:80".to_owned(),
+            port: 80,
+            session: vec![1, 2, 3],
+            timeout: None,
+        }
+    );
+
+    let err = block_on(Connection::builder().timeout(5).build()).unwrap_err();
+    assert_eq!(
+        err,
+        ConnectionBuilderError::DefaultError {
+            field: "session",
+            message: "connection reset".to_owned(),
+        }
+    );
+}
+
+// a minimal executor, the futures above never have to wait for anything
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// With #[builder(no_std)] the generated code only refers to ::core and
+// ::alloc, so it can be used in crates without std. The builder error then
+// doesn't implement std::error::Error, it still implements Debug and Display.
+//
+// This is opted into per type rather than through a crate feature: features
+// are unified across the whole build, so one no_std crate turning it on
+// would break every std crate deriving Builder.
+//
+// The crate using the builder has to declare `extern crate alloc`. This test
+// still links std under another name to get a main function, but `std` itself
+// can't be named from generated code.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+fn check_baud(baud: &u32) -> Result<(), String> {
+    if *baud > 115_200 {
+        return Err("too fast".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std, pattern = "immutable")]
+pub struct Uart<T: Clone + Default> {
+    #[builder(validate = "check_baud")]
+    baud: u32,
+    #[builder(each = "pin")]
+    pins: Vec<u8>,
+    label: Option<String>,
+    #[builder(default)]
+    buffer: T,
+}
+
+fn main() {
+    let uart = Uart::<[u8; 4]>::builder()
+        .baud(9600)
+        .pin(1)
+        .pin(2)
+        .label("console".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(
+        uart,
+        Uart {
+            baud: 9600,
+            pins: alloc::vec![1, 2],
+            label: Some("console".to_string()),
+            buffer: [0; 4],
+        }
+    );
+
+    let err = Uart::<[u8; 4]>::builder().build().unwrap_err();
+    assert_eq!(err.to_string(), "missing required field `baud`");
+
+    let err = Uart::<[u8; 4]>::builder()
+        .baud(1_000_000)
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "invalid value for field `baud`: too fast");
+}
@@ -0,0 +1,50 @@
+//! Everything generated for a public builder is documented, so a crate that
+//! denies missing docs can derive Builder on its public types.
+//!
+//! The builder struct names the fields that have to be set before build(),
+//! and every setter carries the `///` docs of its field followed by a note on
+//! whether the field is required, optional or has a default, or how a
+//! collection's setters add to it.
+//!
+//! Denying missing docs only shows that every item has some doc. That the
+//! field's own docs reach its setters is checked in 66-forwarded-docs.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+/// A command to run.
+#[derive(Builder)]
+pub struct Command {
+    /// Program to execute.
+    pub executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    /// Working directory, the current one if not set.
+    pub current_dir: Option<String>,
+    /// How often to retry.
+    #[builder(default = "3")]
+    pub retries: u32,
+}
+
+/// A shape that can be drawn.
+#[derive(Builder)]
+pub enum Shape {
+    /// A circle around the origin.
+    Circle {
+        /// Radius of the circle.
+        radius: f64,
+    },
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("doc".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.retries, 3);
+
+    let _ = Shape::circle_builder().radius(1.0).build().unwrap();
+}
@@ -0,0 +1,84 @@
+// Values that have to be converted before they can be stored, like ports
+// parsed from strings, get a second setter with #[builder(setter(try_into))]:
+//
+//     impl ServerBuilder {
+//         fn try_port<V: TryInto<u16>>(&mut self, port: V) -> Result<&mut Self, V::Error> {
+//             ...
+//         }
+//     }
+//
+// so that a failed conversion is reported right where the value is set. The
+// attribute can also be given on the struct to get try setters for every
+// field that has a setter taking the whole value.
+
+use derive_builder::Builder;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Timeout(Duration);
+
+impl TryFrom<&str> for Timeout {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.strip_suffix('s') {
+            Some(secs) => secs
+                .parse()
+                .map(|secs| Timeout(Duration::from_secs(secs)))
+                .map_err(|e| format!("{}", e)),
+            None => Err(format!("`{}` is missing a unit", s)),
+        }
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    host: String,
+    #[builder(setter(try_into))]
+    port: u16,
+    #[builder(setter(try_into))]
+    timeout: Option<Timeout>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate, setter(try_into))]
+pub struct Limits {
+    files: u16,
+    depth: Option<u8>,
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    builder.host("localhost".to_owned());
+    assert!(builder.try_port(70_000_u32).is_err());
+    builder.try_port(8080_u32).unwrap().try_timeout("30s").unwrap();
+    let err = builder.try_timeout("30").err().unwrap();
+    assert_eq!(err, "`30` is missing a unit");
+
+    let server = builder.build().unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost".to_owned(),
+            port: 8080,
+            timeout: Some(Timeout(Duration::from_secs(30))),
+        }
+    );
+
+    let limits = Limits::builder()
+        .try_files(1024_i64)
+        .unwrap()
+        .try_depth(8_u64)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(
+        limits,
+        Limits {
+            files: 1024,
+            depth: Some(8),
+        }
+    );
+    assert!(Limits::builder().try_files(-1_i64).is_err());
+}
@@ -0,0 +1,14 @@
+// A collection with an `each` setter always starts out empty and is filled
+// one item at a time, so a default for it would never be used. Rather than
+// silently dropping it, the combination is rejected.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", default = "vec![\"-v\".to_owned()]")]
+    args: Vec<String>,
+}
+
+fn main() {}
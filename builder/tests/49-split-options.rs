@@ -0,0 +1,52 @@
+// Options grouped under setter(...) or build_fn(...) can be spread over
+// several #[builder(...)] attributes, for example to keep each line short or
+// to have a macro add some of them. Only the options inside have to be
+// unique, so repeating e.g. setter(into) is still an error.
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq)]
+pub enum MyError {
+    Invalid(String),
+}
+
+impl From<CommandBuilderError> for MyError {
+    fn from(err: CommandBuilderError) -> Self {
+        MyError::Invalid(err.to_string())
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(setter(into))]
+#[builder(setter(prefix = "with_"))]
+#[builder(build_fn(error = "MyError"))]
+#[builder(build_fn(validate = "check_command"))]
+pub struct Command {
+    executable: String,
+    #[builder(setter(name = "in_dir"))]
+    #[builder(setter(prefix = "ignored_"))]
+    current_dir: Option<String>,
+}
+
+fn check_command(builder: &CommandBuilder) -> Result<(), String> {
+    match builder.executable {
+        Some(ref executable) if executable.is_empty() => Err("empty executable".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .with_executable("cargo")
+        .in_dir("..")
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let err = Command::builder().with_executable("").build().unwrap_err();
+    assert_eq!(
+        err,
+        MyError::Invalid("validation failed: empty executable".to_owned()),
+    );
+}
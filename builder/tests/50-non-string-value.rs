@@ -0,0 +1,13 @@
+// Options taking a string reject any other kind of literal, pointing at the
+// literal and showing how the option is spelled out.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = 1)]
+    args: Vec<String>,
+}
+
+fn main() {}
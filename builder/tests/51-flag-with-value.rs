@@ -0,0 +1,19 @@
+// Options that are switched on just by being there, like sub_builder, don't
+// take a value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder = true)]
+    env: Env,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+// The builder pattern is one of `owned`, `mutable` or `immutable`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "borrowed")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
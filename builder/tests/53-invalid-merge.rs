@@ -0,0 +1,12 @@
+// Merging a collection either appends to it or replaces it, nothing else.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", merge = "extend")]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Options grouping other options, like setter(...), have to be given as a
+// list even when there is only one option inside.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter = "into")]
+    executable: String,
+}
+
+fn main() {}
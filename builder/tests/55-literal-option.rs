@@ -0,0 +1,13 @@
+// Every option inside builder(...) has a name, a bare literal is not an
+// option, and the error lists the ones that are.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder("each")]
+    args: Vec<String>,
+}
+
+fn main() {}
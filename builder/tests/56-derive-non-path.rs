@@ -0,0 +1,11 @@
+// derive(...) takes the names of the traits to derive on the builder.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Clone, "Debug"))]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Each setter of a typestate builder returns a builder of a different type,
+// so the builder can only be taken by value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate, pattern = "mutable")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
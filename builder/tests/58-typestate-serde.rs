@@ -0,0 +1,13 @@
+// A deserialized builder would have to pick its type before seeing which of
+// the required fields the document sets, so typestate builders can't derive
+// serde's traits.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate, derive(Deserialize))]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Every variant of an enum gets a builder of its own, so options that only
+// make sense for a single builder are rejected on enums.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(default)]
+pub enum Shape {
+    Circle { radius: u32 },
+}
+
+#[derive(Builder)]
+#[builder(name = "ShapeMaker")]
+pub enum Figure {
+    Square { side: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+// A nested builder is found by adding `Builder` to the name of the field's
+// type, so the type has to be a plain path like `Env` or `env::Env`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder)]
+    envs: [Env; 2],
+}
+
+fn main() {}
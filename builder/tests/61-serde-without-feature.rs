@@ -0,0 +1,14 @@
+// Deriving serde's traits on the builder needs the `serde` feature of
+// derive_builder, which is off by default. Without it the derive points at
+// the trait asking for the feature rather than failing somewhere inside the
+// generated code.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Clone, Deserialize))]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
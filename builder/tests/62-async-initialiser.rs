@@ -0,0 +1,16 @@
+// An async initialiser can only be awaited by an async build(), so
+// `default_with_async` needs #[builder(build_fn(async))] on the struct.
+
+use derive_builder::Builder;
+
+async fn resolve() -> Result<String, String> {
+    Ok("127.0.0.1:80".to_owned())
+}
+
+#[derive(Builder)]
+pub struct Connection {
+    #[builder(default_with_async = "resolve")]
+    address: String,
+}
+
+fn main() {}
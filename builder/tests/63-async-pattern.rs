@@ -0,0 +1,12 @@
+// An async build() takes the builder by value, so it can't be combined with
+// setters that only hand out a reference to it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "mutable", build_fn(async))]
+pub struct Connection {
+    address: String,
+}
+
+fn main() {}
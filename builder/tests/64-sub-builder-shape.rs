@@ -0,0 +1,30 @@
+// A nested builder is reached through a `&mut` accessor, built by reference
+// and merged through its own merge(), so it has to be a plain builder with
+// the default `mutable` pattern and the default name of `{Type}Builder`.
+// Anything else is reported at the sub_builder option of the field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(name = "MountMaker")]
+pub struct Mount {
+    source: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(sub_builder)]
+    env: Env,
+    #[builder(sub_builder)]
+    mount: Mount,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// The builders of an enum's variants share the options given on the enum,
+// and their fields take options as usual. Options on a variant itself are
+// rejected rather than silently ignored.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub enum Shape {
+    #[builder(name = "CircleMaker", bogus)]
+    Circle { radius: u32 },
+    Square { side: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+// Setters carry the doc attributes of their field, the `///` text as well as
+// anything written as #[doc(...)], since `///` is only sugar for
+// #[doc = "..."]. rustc can't see the text itself, but it does check doc
+// aliases: an alias that repeats the item's own name is an error.
+//
+// Here the field `cwd` gets a setter called `current_dir`. The alias is fine on
+// the field, so the error below can only come from the copy of the field's
+// docs that was forwarded to the setter, along with its `///` line.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    /// Working directory of the program.
+    #[doc(alias = "current_dir")]
+    #[builder(setter(name = "current_dir"))]
+    cwd: String,
+}
+
+fn main() {}
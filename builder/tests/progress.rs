@@ -10,4 +10,64 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-generics.rs");
+    t.pass("tests/11-error-type.rs");
+    t.pass("tests/12-custom-error-type.rs");
+    t.pass("tests/13-default-values.rs");
+    t.pass("tests/14-typestate.rs");
+    t.compile_fail("tests/15-typestate-missing-field.rs");
+    t.pass("tests/16-owned-pattern.rs");
+    t.pass("tests/17-immutable-pattern.rs");
+    t.pass("tests/18-validation.rs");
+    t.pass("tests/19-setter-into.rs");
+    t.pass("tests/20-each-collections.rs");
+    t.pass("tests/21-tuple-struct.rs");
+    t.pass("tests/22-enum.rs");
+    t.compile_fail("tests/23-union.rs");
+    t.pass("tests/24-multiple-builders.rs");
+    t.pass("tests/25-redefined-prelude-everywhere.rs");
+    t.pass("tests/26-sub-builder.rs");
+    #[cfg(feature = "serde")]
+    t.pass("tests/27-serde.rs");
+    t.pass("tests/28-merge.rs");
+    t.compile_fail("tests/29-merge-non-collection.rs");
+    t.pass("tests/30-visibility.rs");
+    t.compile_fail("tests/31-private-builder.rs");
+    t.pass("tests/32-setter-names.rs");
+    t.compile_fail("tests/33-setter-name-collision.rs");
+    t.compile_fail("tests/34-skip-without-default.rs");
+    t.compile_fail("tests/35-unknown-option.rs");
+    t.compile_fail("tests/36-duplicate-option.rs");
+    t.compile_fail("tests/37-wrong-literal.rs");
+    t.compile_fail("tests/38-missing-value.rs");
+    t.compile_fail("tests/39-conflicting-options.rs");
+    t.compile_fail("tests/40-each-setter-span.rs");
+    t.compile_fail("tests/41-malformed-attribute.rs");
+    t.compile_fail("tests/42-unknown-struct-option.rs");
+    t.pass("tests/43-default-with.rs");
+    t.pass("tests/44-async-build.rs");
+    t.pass("tests/45-no-std.rs");
+    t.pass("tests/46-docs.rs");
+    t.pass("tests/47-try-setters.rs");
+    t.compile_fail("tests/48-each-with-default.rs");
+    t.pass("tests/49-split-options.rs");
+    t.compile_fail("tests/50-non-string-value.rs");
+    t.compile_fail("tests/51-flag-with-value.rs");
+    t.compile_fail("tests/52-invalid-pattern.rs");
+    t.compile_fail("tests/53-invalid-merge.rs");
+    t.compile_fail("tests/54-list-without-parentheses.rs");
+    t.compile_fail("tests/55-literal-option.rs");
+    t.compile_fail("tests/56-derive-non-path.rs");
+    t.compile_fail("tests/57-typestate-pattern.rs");
+    #[cfg(feature = "serde")]
+    t.compile_fail("tests/58-typestate-serde.rs");
+    t.compile_fail("tests/59-enum-options.rs");
+    t.compile_fail("tests/60-sub-builder-non-path.rs");
+    #[cfg(not(feature = "serde"))]
+    t.compile_fail("tests/61-serde-without-feature.rs");
+    t.compile_fail("tests/62-async-initialiser.rs");
+    t.compile_fail("tests/63-async-pattern.rs");
+    t.compile_fail("tests/64-sub-builder-shape.rs");
+    t.compile_fail("tests/65-variant-options.rs");
+    t.compile_fail("tests/66-forwarded-docs.rs");
 }
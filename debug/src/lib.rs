@@ -1,10 +1,481 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashSet;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{parse_macro_input, DeriveInput, Token};
 
-#[proc_macro_derive(CustomDebug)]
+#[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
 
-    unimplemented!()
+    match expand(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+// what #[debug(redact)] prints in place of the field's value
+const REDACTED: &str = "[REDACTED]";
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let input_ident = &input.ident;
+    let struct_attrs = get_struct_attrs(&input.attrs)?;
+
+    let arms = match input.data {
+        syn::Data::Struct(ref data) => {
+            check_allowed_fields(&struct_attrs, &data.fields)?;
+            let (pattern, body) = debug_fields(input_ident, &data.fields, &struct_attrs)?;
+            vec![quote!(Self #pattern => #body)]
+        }
+        syn::Data::Enum(ref data) => {
+            check_allowed_fields(
+                &struct_attrs,
+                data.variants.iter().flat_map(|variant| &variant.fields),
+            )?;
+            data.variants
+                .iter()
+                .map(|variant| {
+                    // options go on the enum or on the fields, one on a variant would be
+                    // ignored and could leave e.g. a secret unredacted
+                    if let Some(attr) = variant.attrs.iter().find(|a| a.path.is_ident("debug")) {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "debug options are not supported on enum variants, put them on the variant's fields",
+                        ));
+                    }
+                    let variant_ident = &variant.ident;
+                    let (pattern, body) =
+                        debug_fields(variant_ident, &variant.fields, &struct_attrs)?;
+                    Ok(quote!(Self::#variant_ident #pattern => #body))
+                })
+                .collect::<syn::Result<_>>()?
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input_ident,
+                "CustomDebug cannot be derived for unions",
+            ))
+        }
+    };
+
+    let bounds = match struct_attrs.bound {
+        Some(ref bound) => bound.clone(),
+        None => infer_bounds(input, &struct_attrs)?,
+    };
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics std::fmt::Debug for #input_ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+// The pattern binding every shown field of a struct or variant by reference,
+// and the expression writing them out through the matching Formatter helper.
+fn debug_fields(
+    ident: &syn::Ident,
+    fields: &syn::Fields,
+    struct_attrs: &StructAttrs,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let name = ident.unraw().to_string();
+
+    let mut patterns = Vec::new();
+    let mut entries = Vec::new();
+    let mut non_exhaustive = false;
+    let mut uses_with = false;
+
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = get_field_attrs(field)?;
+        let binding = match field.ident {
+            Some(ref ident) => format_ident!("__field_{}", ident),
+            None => format_ident!("__field_{}", i),
+        };
+
+        if attrs.skip.is_some() {
+            patterns.push(quote!(_));
+            non_exhaustive = true;
+            continue;
+        }
+        patterns.push(quote!(ref #binding));
+
+        let value = match (
+            redaction(field, &attrs, struct_attrs),
+            attrs.format,
+            attrs.with,
+        ) {
+            (Some(placeholder), _, _) => quote!(&std::format_args!("{}", #placeholder)),
+            (None, Some(format), _) => quote!(&std::format_args!(#format, #binding)),
+            (None, None, Some(with)) => {
+                uses_with = true;
+                quote!(&__DebugWith(#binding, #with))
+            }
+            (None, None, None) => quote!(#binding),
+        };
+        entries.push(match field.ident {
+            Some(ref ident) => {
+                let name = ident.unraw().to_string();
+                quote!(.field(#name, #value))
+            }
+            None => quote!(.field(#value)),
+        });
+    }
+
+    let finish = if non_exhaustive {
+        quote!(finish_non_exhaustive)
+    } else {
+        quote!(finish)
+    };
+
+    let (pattern, body) = match fields {
+        syn::Fields::Named(_) => {
+            let members = fields.iter().map(|field| field.ident.as_ref().unwrap());
+            (
+                quote!({ #(#members: #patterns),* }),
+                quote!(f.debug_struct(#name) #(#entries)* .#finish()),
+            )
+        }
+        syn::Fields::Unnamed(_) => (
+            quote!((#(#patterns),*)),
+            quote!(f.debug_tuple(#name) #(#entries)* .#finish()),
+        ),
+        syn::Fields::Unit => (quote!(), quote!(f.write_str(#name))),
+    };
+
+    if !uses_with {
+        return Ok((pattern, body));
+    }
+
+    // Fields formatted by a function are handed to the Formatter helpers
+    // wrapped in a Debug impl calling it, so that the function gets the same
+    // Formatter, flags like {:#?} included, as any other field would.
+    let body = quote! {{
+        struct __DebugWith<'a, T: ?Sized>(
+            &'a T,
+            fn(&T, &mut std::fmt::Formatter) -> std::fmt::Result,
+        );
+
+        impl<T: ?Sized> std::fmt::Debug for __DebugWith<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                (self.1)(self.0, f)
+            }
+        }
+
+        #body
+    }};
+
+    Ok((pattern, body))
+}
+
+// the placeholder shown instead of a field's value, if it is redacted
+fn redaction(
+    field: &syn::Field,
+    attrs: &FieldAttrs,
+    struct_attrs: &StructAttrs,
+) -> Option<syn::LitStr> {
+    if let Some(ref placeholder) = attrs.redact {
+        return Some(placeholder.clone());
+    }
+    let allowed = struct_attrs.redact_all_except.as_ref()?;
+    match field.ident {
+        Some(ref ident) if allowed.contains(ident) => None,
+        _ => Some(syn::LitStr::new(REDACTED, proc_macro2::Span::call_site())),
+    }
+}
+
+// Infers the where clause of the impl from the fields handed to Debug as
+// they are: every type parameter they use needs to be Debug, and so does
+// every associated type of one, like `T::Value`. Fields that are skipped,
+// redacted or formatted by a function need nothing, and neither does
+// anything inside a PhantomData.
+//
+// Only type parameters are ever bounded, never whole field types, so types
+// referring to each other like One<T> { two: Option<Box<Two<T>>> } and
+// Two<T> { one: Box<One<T>> } do not send the compiler around in circles.
+fn infer_bounds(
+    input: &DeriveInput,
+    struct_attrs: &StructAttrs,
+) -> syn::Result<Vec<syn::WherePredicate>> {
+    let fields: Vec<&syn::Field> = match input.data {
+        syn::Data::Struct(ref data) => data.fields.iter().collect(),
+        syn::Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
+
+    let mut visitor = BoundVisitor {
+        params: input
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect(),
+        types: Vec::new(),
+    };
+    let mut bounds = Vec::new();
+
+    for field in fields {
+        let attrs = get_field_attrs(field)?;
+        if let Some(bound) = attrs.bound {
+            bounds.extend(bound);
+        } else if attrs.skip.is_none()
+            && attrs.with.is_none()
+            && redaction(field, &attrs, struct_attrs).is_none()
+        {
+            visitor.visit_type(&field.ty);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for ty in visitor.types {
+        if seen.insert(ty.to_token_stream().to_string()) {
+            bounds.push(syn::parse_quote!(#ty: std::fmt::Debug));
+        }
+    }
+
+    Ok(bounds)
+}
+
+struct BoundVisitor<'a> {
+    params: Vec<&'a syn::Ident>,
+    // the type parameters and associated types found so far
+    types: Vec<syn::TypePath>,
+}
+
+impl<'ast> Visit<'ast> for BoundVisitor<'_> {
+    fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+        let last = ty.path.segments.last().unwrap();
+        if last.ident == "PhantomData" {
+            return;
+        }
+
+        // `T`, `T::Value` or `<T as Trait>::Value`
+        let param = match ty.qself {
+            Some(ref qself) => match *qself.ty {
+                syn::Type::Path(ref path) if path.qself.is_none() => path.path.get_ident(),
+                _ => None,
+            },
+            None => ty.path.segments.first().map(|segment| &segment.ident),
+        };
+        if param.is_some_and(|param| self.params.contains(&param)) {
+            self.types.push(ty.clone());
+            return;
+        }
+
+        visit::visit_type_path(self, ty);
+    }
+}
+
+// every field left readable by redact_all_except(...) has to exist
+fn check_allowed_fields<'a>(
+    struct_attrs: &StructAttrs,
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+) -> syn::Result<()> {
+    let allowed = match struct_attrs.redact_all_except {
+        Some(ref allowed) => allowed,
+        None => return Ok(()),
+    };
+    let names = fields
+        .into_iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect::<Vec<_>>();
+
+    match allowed.iter().find(|ident| !names.contains(ident)) {
+        Some(ident) => Err(syn::Error::new_spanned(
+            ident,
+            format!("no field named `{}`", ident),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    // #[debug(redact_all_except(a, b))]
+    redact_all_except: Option<Vec<syn::Ident>>,
+    // #[debug(bound = "...")], replacing every inferred bound
+    bound: Option<Vec<syn::WherePredicate>>,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    // #[debug = "..."]
+    format: Option<syn::LitStr>,
+    // #[debug(skip)]
+    skip: Option<syn::Path>,
+    // #[debug(redact)] or #[debug(redact = "...")]
+    redact: Option<syn::LitStr>,
+    // #[debug(with = "path::to::fn")]
+    with: Option<syn::ExprPath>,
+    // #[debug(bound = "...")], replacing the bounds inferred from this field
+    bound: Option<Vec<syn::WherePredicate>>,
+}
+
+fn get_struct_attrs(attrs: &[syn::Attribute]) -> syn::Result<StructAttrs> {
+    let mut struct_attrs = StructAttrs::default();
+
+    for nested in debug_options(attrs, false)? {
+        match nested {
+            syn::Meta::List(ref list) if list.path.is_ident("redact_all_except") => {
+                let idents = list
+                    .nested
+                    .iter()
+                    .map(|nested| match nested {
+                        syn::NestedMeta::Meta(syn::Meta::Path(path))
+                            if path.get_ident().is_some() =>
+                        {
+                            Ok(path.get_ident().unwrap().clone())
+                        }
+                        _ => Err(syn::Error::new_spanned(nested, "expected a field name")),
+                    })
+                    .collect::<syn::Result<_>>()?;
+                set(&mut struct_attrs.redact_all_except, idents, &nested)?;
+            }
+            syn::Meta::NameValue(syn::MetaNameValue {
+                ref path,
+                lit: syn::Lit::Str(ref s),
+                ..
+            }) if path.is_ident("bound") => set(&mut struct_attrs.bound, parse_bound(s)?, &nested)?,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    nested,
+                    "unknown debug option, expected `redact_all_except(...)` or `bound = \"...\"`",
+                ))
+            }
+        }
+    }
+
+    Ok(struct_attrs)
+}
+
+fn get_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut field_attrs = FieldAttrs::default();
+
+    for nested in debug_options(&field.attrs, true)? {
+        match nested {
+            // the bare #[debug = "..."] form comes back under the name `debug`
+            syn::Meta::NameValue(syn::MetaNameValue {
+                ref path,
+                lit: syn::Lit::Str(ref s),
+                ..
+            }) if path.is_ident("debug") => set(&mut field_attrs.format, s.clone(), &nested)?,
+            syn::Meta::Path(ref path) if path.is_ident("skip") => {
+                set(&mut field_attrs.skip, path.clone(), &nested)?
+            }
+            syn::Meta::Path(ref path) if path.is_ident("redact") => {
+                let placeholder = syn::LitStr::new(REDACTED, path.span());
+                set(&mut field_attrs.redact, placeholder, &nested)?
+            }
+            syn::Meta::NameValue(syn::MetaNameValue {
+                ref path,
+                lit: syn::Lit::Str(ref s),
+                ..
+            }) if path.is_ident("redact") => set(&mut field_attrs.redact, s.clone(), &nested)?,
+            syn::Meta::NameValue(syn::MetaNameValue {
+                ref path,
+                lit: syn::Lit::Str(ref s),
+                ..
+            }) if path.is_ident("with") => set(&mut field_attrs.with, s.parse()?, &nested)?,
+            syn::Meta::NameValue(syn::MetaNameValue {
+                ref path,
+                lit: syn::Lit::Str(ref s),
+                ..
+            }) if path.is_ident("bound") => set(&mut field_attrs.bound, parse_bound(s)?, &nested)?,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    nested,
+                    "unknown debug option, expected `skip`, `redact`, `redact = \"...\"`, \
+                     `with = \"...\"` or `bound = \"...\"`",
+                ))
+            }
+        }
+    }
+
+    if let Some(ref skip) = field_attrs.skip {
+        if field_attrs.format.is_some()
+            || field_attrs.redact.is_some()
+            || field_attrs.with.is_some()
+        {
+            return Err(syn::Error::new_spanned(
+                skip,
+                "a skipped field cannot also be formatted or redacted",
+            ));
+        }
+    }
+
+    if let Some(ref redact) = field_attrs.redact {
+        if field_attrs.format.is_some() {
+            return Err(syn::Error::new_spanned(
+                redact,
+                "a redacted field cannot also have a format string",
+            ));
+        }
+        if field_attrs.with.is_some() {
+            return Err(syn::Error::new_spanned(
+                redact,
+                "a redacted field cannot also have a `with` function",
+            ));
+        }
+    }
+
+    if let (Some(_), Some(ref with)) = (&field_attrs.format, &field_attrs.with) {
+        return Err(syn::Error::new_spanned(
+            with,
+            "a field cannot have both a format string and a `with` function",
+        ));
+    }
+
+    Ok(field_attrs)
+}
+
+// Flattens every #[debug(...)] attribute into its options. Where `bare` is
+// set, #[debug = "..."] is passed along as it is.
+fn debug_options(attrs: &[syn::Attribute], bare: bool) -> syn::Result<Vec<syn::Meta>> {
+    let mut options = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("debug")) {
+        match attr.parse_meta()? {
+            syn::Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        syn::NestedMeta::Meta(meta) => options.push(meta),
+                        syn::NestedMeta::Lit(lit) => {
+                            return Err(syn::Error::new_spanned(lit, "expected a debug option"))
+                        }
+                    }
+                }
+            }
+            meta @ syn::Meta::NameValue(_) if bare => options.push(meta),
+            meta => return Err(syn::Error::new_spanned(meta, "expected `debug(...)`")),
+        }
+    }
+
+    Ok(options)
+}
+
+// the where predicates in a bound = "..." option, which may be empty
+fn parse_bound(s: &syn::LitStr) -> syn::Result<Vec<syn::WherePredicate>> {
+    let predicates = s.parse_with(Punctuated::<_, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+fn set<T>(slot: &mut Option<T>, value: T, tokens: impl quote::ToTokens) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(tokens, "duplicate debug option"));
+    }
+    *slot = Some(value);
+    Ok(())
 }
@@ -0,0 +1,61 @@
+// Besides structs with named fields, the derive should handle tuple structs,
+// unit structs and enums, formatting each the same way the standard library's
+// #[derive(Debug)] would: tuple-like shapes through debug_tuple, named fields
+// through debug_struct and unit-like ones as just their name.
+//
+// The #[debug = "..."] attribute keeps working on fields inside enum variants.
+//
+//
+// Resources:
+//
+//   - The Formatter helpers for each shape:
+//     https://doc.rust-lang.org/std/fmt/struct.Formatter.html#method.debug_tuple
+//     https://doc.rust-lang.org/std/fmt/struct.Formatter.html#method.debug_struct
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Id(#[debug = "{:#x}"] u32, &'static str);
+
+#[derive(CustomDebug)]
+pub struct Ack;
+
+#[derive(CustomDebug)]
+pub enum Message {
+    Ping,
+    Data(Id, #[debug = "0b{:04b}"] u8),
+    Close {
+        code: u16,
+        #[debug = "{:?}!"]
+        reason: &'static str,
+    },
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn main() {
+    let id = Id(255, "main");
+    assert_eq!(format!("{:?}", id), r#"Id(0xff, "main")"#);
+    assert_eq!(format!("{:?}", Ack), "Ack");
+
+    assert_eq!(format!("{:?}", Message::Ping), "Ping");
+
+    let data = Message::Data(id, 5);
+    assert_eq!(format!("{:?}", data), r#"Data(Id(0xff, "main"), 0b0101)"#);
+
+    let close = Message::Close {
+        code: 1000,
+        reason: "bye",
+    };
+    assert_eq!(
+        format!("{:?}", close),
+        r#"Close { code: 1000, reason: "bye"! }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", close),
+        "Close {\n    code: 1000,\n    reason: \"bye\"!,\n}",
+    );
+
+    let _ = |never: &Never| format!("{:?}", never);
+}
@@ -0,0 +1,96 @@
+// Values like tokens and passwords must never end up in logs. A field marked
+// #[debug(skip)] is left out of the output entirely, which is signalled to the
+// reader by a trailing `..` as Formatter's finish_non_exhaustive() prints it.
+// A field marked #[debug(redact)] stays in the output with its value replaced
+// by a placeholder, either the default `[REDACTED]` or the one given with
+// #[debug(redact = "...")].
+//
+// For types that are mostly secret, the struct-level
+// #[debug(redact_all_except(...))] turns this around and redacts every field
+// except the listed ones.
+//
+//
+// Resources:
+//
+//   - Formatting a struct that has more fields than it shows:
+//     https://doc.rust-lang.org/std/fmt/struct.DebugStruct.html#method.finish_non_exhaustive
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Login {
+    user: &'static str,
+    #[debug(redact)]
+    password: &'static str,
+    #[debug(redact = "***")]
+    otp: u32,
+    #[debug(skip)]
+    session: u64,
+}
+
+#[derive(CustomDebug)]
+pub struct Token(&'static str, #[debug(skip)] u64);
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(kind, expires))]
+pub struct Credentials {
+    kind: &'static str,
+    key: &'static str,
+    #[debug(redact = "<secret>")]
+    secret: &'static str,
+    #[debug = "{}s"]
+    expires: u32,
+    #[debug(skip)]
+    nonce: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(user))]
+pub enum Auth {
+    Password {
+        user: &'static str,
+        password: &'static str,
+    },
+    Bearer(&'static str),
+}
+
+fn main() {
+    let login = Login {
+        user: "ferris",
+        password: "hunter2",
+        otp: 123456,
+        session: 42,
+    };
+    assert_eq!(
+        format!("{:?}", login),
+        r#"Login { user: "ferris", password: [REDACTED], otp: ***, .. }"#,
+    );
+
+    let token = Token("refresh", 7);
+    assert_eq!(format!("{:?}", token), r#"Token("refresh", ..)"#);
+
+    let credentials = Credentials {
+        kind: "aws",
+        key: "AKIA",
+        secret: "wJalr",
+        expires: 3600,
+        nonce: 1,
+    };
+    assert_eq!(
+        format!("{:?}", credentials),
+        r#"Credentials { kind: "aws", key: [REDACTED], secret: <secret>, expires: 3600s, .. }"#,
+    );
+
+    let password = Auth::Password {
+        user: "ferris",
+        password: "hunter2",
+    };
+    assert_eq!(
+        format!("{:?}", password),
+        r#"Password { user: "ferris", password: [REDACTED] }"#,
+    );
+    assert_eq!(format!("{:?}", Auth::Bearer("abc")), "Bearer([REDACTED])");
+
+    let _ = (login.password, login.otp, login.session, token.1);
+    let _ = (credentials.key, credentials.secret, credentials.nonce);
+}
@@ -0,0 +1,15 @@
+// Every field named in #[debug(redact_all_except(...))] has to exist, so that
+// a typo or a renamed field does not silently redact the field it was meant
+// to keep readable.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(name, ide))]
+pub struct Account {
+    name: &'static str,
+    id: u64,
+    key: &'static str,
+}
+
+fn main() {}
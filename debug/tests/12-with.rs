@@ -0,0 +1,82 @@
+// Some values need more than a format string to be readable, like a byte
+// buffer best shown as hex. #[debug(with = "path::to::fn")] names a function
+// taking the field by reference together with the Formatter:
+//
+//     fn hex(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+//         ...
+//     }
+//
+// The function is handed the same Formatter the derived impl is writing to, so
+// it can tell {:?} from {:#?} and use Formatter's helpers like any hand-written
+// Debug impl would.
+//
+//
+// Resources:
+//
+//   - Checking for the alternate {:#?} flag:
+//     https://doc.rust-lang.org/std/fmt/struct.Formatter.html#method.alternate
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+mod format {
+    use std::fmt;
+
+    pub fn hex(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+
+    pub fn pairs<T: AsRef<str>>(pairs: &Vec<(T, T)>, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(pairs.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+            .finish()
+    }
+}
+
+fn seconds(secs: &u64, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}m{}s", secs / 60, secs % 60)
+}
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(with = "format::hex")]
+    payload: Vec<u8>,
+    #[debug(with = "format::pairs")]
+    headers: Vec<(&'static str, &'static str)>,
+    #[debug(with = "seconds")]
+    ttl: u64,
+}
+
+#[derive(CustomDebug)]
+pub enum Frame {
+    Data(#[debug(with = "format::hex")] Vec<u8>),
+    Close,
+}
+
+fn main() {
+    let packet = Packet {
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        headers: vec![("kind", "ping")],
+        ttl: 90,
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        r#"Packet { payload: deadbeef, headers: {"kind": "ping"}, ttl: 1m30s }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", packet),
+        r#"Packet {
+    payload: deadbeef,
+    headers: {
+        "kind": "ping",
+    },
+    ttl: 1m30s,
+}"#,
+    );
+
+    assert_eq!(format!("{:?}", Frame::Data(vec![1, 2])), "Data(0102)");
+    assert_eq!(format!("{:?}", Frame::Close), "Close");
+}
@@ -0,0 +1,106 @@
+// The heuristics from the earlier test cases have to hold up when they all
+// come together in one type. Here a single struct mixes a type parameter that
+// only ever appears in a PhantomData, one reached through an associated type,
+// and one that is part of a recursive structure through a private type, all
+// while other fields are skipped, redacted or formatted by a function and so
+// need no bound at all.
+//
+// The impl for Graph below is expected to come out as:
+//
+//     impl<K: Key, M, T, S> Debug for Graph<K, M, T, S>
+//     where
+//         K::Id: Debug,
+//         T: Debug,
+//     {...}
+//
+// and #[debug(bound = "...")] on a field replaces only what would have been
+// inferred from that field.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+pub trait Key {
+    type Id;
+}
+
+#[derive(CustomDebug)]
+pub struct Graph<K: Key, M, T, S> {
+    ids: Vec<K::Id>,
+    marker: PhantomData<M>,
+    root: Option<Box<Node<T>>>,
+    #[debug(skip)]
+    secret: S,
+    #[debug(redact)]
+    token: S,
+    #[debug(with = "count")]
+    tags: Vec<S>,
+}
+
+#[derive(CustomDebug)]
+struct Node<T> {
+    value: T,
+    edges: Vec<Edge<T>>,
+}
+
+#[derive(CustomDebug)]
+struct Edge<T> {
+    to: Box<Node<T>>,
+}
+
+#[derive(CustomDebug)]
+pub enum Lookup<K: Key, L> {
+    Found(<K as Key>::Id),
+    Cached {
+        #[debug(bound = "")]
+        hint: PhantomData<L>,
+        #[debug(bound = "L: Clone + Debug")]
+        last: L,
+    },
+}
+
+fn count<S>(tags: &Vec<S>, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} tags", tags.len())
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated type does.
+    struct Id;
+
+    impl Key for Id {
+        type Id = u8;
+    }
+
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Graph<Id, NotDebug, u8, NotDebug>>();
+    assert_debug::<Lookup<Id, u8>>();
+
+    let graph = Graph::<Id, (), u8, NotDebug> {
+        ids: vec![1, 2],
+        marker: PhantomData,
+        root: Some(Box::new(Node {
+            value: 1,
+            edges: vec![Edge {
+                to: Box::new(Node {
+                    value: 2,
+                    edges: Vec::new(),
+                }),
+            }],
+        })),
+        secret: NotDebug,
+        token: NotDebug,
+        tags: vec![NotDebug, NotDebug],
+    };
+    assert_eq!(
+        format!("{:?}", graph),
+        "Graph { ids: [1, 2], marker: PhantomData<()>, \
+         root: Some(Node { value: 1, edges: [Edge { to: Node { value: 2, edges: [] } }] }), \
+         token: [REDACTED], tags: 2 tags, .. }",
+    );
+
+    let _ = (graph.secret, graph.token);
+}
@@ -0,0 +1,28 @@
+// A field is either shown through its format string or `with` function, or
+// redacted, never both, so that it is always clear from the attributes what
+// ends up in the output.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+fn hex(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+#[derive(CustomDebug)]
+pub struct Key {
+    #[debug = "{:x}"]
+    #[debug(redact)]
+    id: u64,
+}
+
+#[derive(CustomDebug)]
+pub struct Secret {
+    #[debug(with = "hex", redact = "***")]
+    bytes: Vec<u8>,
+}
+
+fn main() {}
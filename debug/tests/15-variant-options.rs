@@ -0,0 +1,17 @@
+// Options go on the enum itself or on the fields of its variants. A
+// #[debug(...)] on a variant would otherwise be silently ignored, printing
+// the very fields it was meant to hide, so it is rejected instead.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub enum Auth {
+    #[debug(skip)]
+    Login {
+        user: &'static str,
+        password: &'static str,
+    },
+    Anonymous,
+}
+
+fn main() {}